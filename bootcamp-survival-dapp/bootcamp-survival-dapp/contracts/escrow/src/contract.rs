@@ -1,19 +1,33 @@
 use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Map, String, Symbol, Val, Vec};
 
-use crate::core::{DisputeManager, EscrowManager, MilestoneManager};
+use crate::core::payout::PayoutManager;
+use crate::core::{
+    AdminManager, DisputeManager, DisputeOutcome, EscrowManager, EvidenceManager, FundingManager,
+    MilestoneManager, ShareSplit,
+};
 use crate::error::ContractError;
 use crate::events::handler::{
-    ChgEsc, DisEsc, DisputeResolved, EscrowDisputed, ExtTtlEvt, FundEsc, InitEsc,
-    MilestoneApproved, MilestoneStatusChanged,
+    publish_payouts, CancelEsc, ChgEsc, DisEsc, DisputeProposalSubmitted, DisputeResolved,
+    DisputeRuled, DisputeStatementSubmitted, DisputeWithdrawn, EscrowDisputed, ExtTtlEvt,
+    FullSnapshot, FundEsc, InitEsc, MilestoneApproved, MilestoneChangesRequested,
+    MilestoneDisputeProposed, MilestoneDisputeResolved, MilestoneDisputed, MilestoneExpired,
+    MilestoneReleased, MilestoneStatusChanged, RulingAppealed, StaleDisputeFinalized,
+    TrustlessWorkAddressUpdated, TrustlessWorkFeeUpdated,
+};
+use crate::storage::types::{
+    AddressBalance, Contribution, DisputeInfo, DisputeStatement, Escrow, EscrowStatus,
+    EvidenceEntry, EvidenceSubmission, FeeTotals, Milestone, MilestoneStatus, PayoutTransfer,
+    ProtocolConfig, Ruling,
 };
-use crate::storage::types::{AddressBalance, Escrow};
 
 #[contract]
 pub struct EscrowContract;
 
 #[contractimpl]
 impl EscrowContract {
-    pub fn __constructor() {}
+    pub fn __constructor(e: Env, admin: Address, trustless_work_address: Address) {
+        AdminManager::initialize(&e, admin, trustless_work_address);
+    }
 
     pub fn deploy(
         env: Env,
@@ -44,6 +58,12 @@ impl EscrowContract {
     pub fn initialize_escrow(e: &Env, escrow_properties: Escrow) -> Result<Escrow, ContractError> {
         let initialized_escrow = EscrowManager::initialize_escrow(e, escrow_properties)?;
         InitEsc {
+            engagement_id: initialized_escrow.engagement_id.clone(),
+            platform: initialized_escrow.roles.platform_address.clone(),
+        }
+        .publish(e);
+        FullSnapshot {
+            engagement_id: initialized_escrow.engagement_id.clone(),
             escrow: initialized_escrow.clone(),
         }
         .publish(e);
@@ -57,17 +77,45 @@ impl EscrowContract {
         amount: i128,
     ) -> Result<(), ContractError> {
         EscrowManager::fund_escrow(e, &signer, &expected_escrow, amount)?;
-        FundEsc { signer, amount }.publish(e);
+        FundEsc {
+            engagement_id: EscrowManager::get_escrow_header(e)?.engagement_id,
+            signer,
+            amount,
+        }
+        .publish(e);
         Ok(())
     }
 
-    pub fn release_funds(
+    pub fn release_funds(e: &Env, release_signer: Address) -> Result<(), ContractError> {
+        let plan = EscrowManager::release_funds(e, &release_signer)?;
+        let escrow = EscrowManager::get_escrow_header(e)?;
+        publish_payouts(e, &escrow, &plan);
+        DisEsc {
+            engagement_id: escrow.engagement_id,
+            release_signer,
+        }
+        .publish(e);
+        Ok(())
+    }
+
+    pub fn quote_release(e: &Env) -> Result<Vec<PayoutTransfer>, ContractError> {
+        EscrowManager::quote_release(e)
+    }
+
+    pub fn release_milestone_funds(
         e: &Env,
+        milestone_index: i128,
         release_signer: Address,
-        trustless_work_address: Address,
     ) -> Result<(), ContractError> {
-        EscrowManager::release_funds(e, &release_signer, &trustless_work_address)?;
-        DisEsc { release_signer }.publish(e);
+        let plan = EscrowManager::release_milestone_funds(e, milestone_index, &release_signer)?;
+        let escrow = EscrowManager::get_escrow_header(e)?;
+        publish_payouts(e, &escrow, &plan);
+        MilestoneReleased {
+            engagement_id: escrow.engagement_id,
+            release_signer,
+            milestone_index,
+        }
+        .publish(e);
         Ok(())
     }
 
@@ -82,18 +130,39 @@ impl EscrowContract {
             escrow_properties.clone(),
         )?;
         ChgEsc {
+            engagement_id: updated_escrow.engagement_id.clone(),
             platform: plataform_address,
-            engagement_id: escrow_properties.engagement_id.clone(),
-            new_escrow_properties: updated_escrow.clone(),
+        }
+        .publish(e);
+        FullSnapshot {
+            engagement_id: updated_escrow.engagement_id.clone(),
+            escrow: updated_escrow.clone(),
         }
         .publish(e);
         Ok(updated_escrow)
     }
 
+    pub fn cancel_escrow(e: &Env, signer: Address) -> Result<(), ContractError> {
+        let (refunded_amount, plan) = EscrowManager::cancel_escrow(e, &signer)?;
+        let escrow = EscrowManager::get_escrow_header(e)?;
+        publish_payouts(e, &escrow, &plan);
+        CancelEsc {
+            engagement_id: escrow.engagement_id,
+            signer,
+            refunded_amount,
+        }
+        .publish(e);
+        Ok(())
+    }
+
     pub fn get_escrow(e: &Env) -> Result<Escrow, ContractError> {
         EscrowManager::get_escrow(e)
     }
 
+    pub fn get_status(e: &Env) -> Result<EscrowStatus, ContractError> {
+        EscrowManager::get_status(e)
+    }
+
     pub fn get_escrow_by_contract_id(
         e: &Env,
         contract_id: Address,
@@ -101,6 +170,14 @@ impl EscrowContract {
         EscrowManager::get_escrow_by_contract_id(e, &contract_id)
     }
 
+    pub fn get_contributions(e: &Env) -> Vec<Contribution> {
+        FundingManager::get_contributions(e)
+    }
+
+    pub fn get_fee_totals(e: &Env) -> FeeTotals {
+        PayoutManager::get_fee_totals(e)
+    }
+
     pub fn get_multiple_escrow_balances(
         e: &Env,
         addresses: Vec<Address>,
@@ -112,6 +189,29 @@ impl EscrowContract {
     // Admin / TTL /////
     ////////////////////////
 
+    pub fn get_protocol_config(e: &Env) -> ProtocolConfig {
+        AdminManager::get_protocol_config(e)
+    }
+
+    pub fn set_trustless_work_fee(e: &Env, fee_bps: u32) -> Result<(), ContractError> {
+        let previous = AdminManager::set_trustless_work_fee(e, fee_bps)?;
+        TrustlessWorkFeeUpdated {
+            previous,
+            current: fee_bps,
+        }
+        .publish(e);
+        Ok(())
+    }
+
+    pub fn set_trustless_work_address(e: &Env, address: Address) {
+        let previous = AdminManager::set_trustless_work_address(e, address.clone());
+        TrustlessWorkAddressUpdated {
+            previous,
+            current: address,
+        }
+        .publish(e);
+    }
+
     pub fn extend_contract_ttl(
         e: &Env,
         platform_address: Address,
@@ -119,7 +219,7 @@ impl EscrowContract {
     ) -> Result<(), ContractError> {
         platform_address.require_auth();
 
-        let escrow = EscrowManager::get_escrow(e)?;
+        let escrow = EscrowManager::get_escrow_header(e)?;
         if platform_address != escrow.roles.platform_address {
             return Err(ContractError::OnlyPlatformAddressExecuteThisFunction);
         }
@@ -128,8 +228,83 @@ impl EscrowContract {
         e.storage()
             .instance()
             .extend_ttl(min_ledgers, ledgers_to_extend);
+        FundingManager::extend_contributions_ttl(e, min_ledgers, ledgers_to_extend);
+        MilestoneManager::extend_milestones_ttl(e, min_ledgers, ledgers_to_extend);
+
+        ExtTtlEvt {
+            engagement_id: escrow.engagement_id,
+            platform: platform_address,
+            ledgers_to_extend,
+        }
+        .publish(e);
+
+        Ok(())
+    }
+
+    /// Evidence logs and dispute statements can outgrow a single transaction,
+    /// so they are extended one page at a time rather than by
+    /// `extend_contract_ttl`.
+    pub fn extend_evidence_ttl(
+        e: &Env,
+        platform_address: Address,
+        milestone_index: i128,
+        start: u32,
+        limit: u32,
+        ledgers_to_extend: u32,
+    ) -> Result<(), ContractError> {
+        platform_address.require_auth();
+
+        let escrow = EscrowManager::get_escrow_header(e)?;
+        if platform_address != escrow.roles.platform_address {
+            return Err(ContractError::OnlyPlatformAddressExecuteThisFunction);
+        }
+        MilestoneManager::get_milestone(e, milestone_index as u32)?;
+
+        let min_ledgers = 1u32;
+        EvidenceManager::extend_evidence_ttl(
+            e,
+            milestone_index as u32,
+            start,
+            limit,
+            min_ledgers,
+            ledgers_to_extend,
+        );
 
         ExtTtlEvt {
+            engagement_id: escrow.engagement_id,
+            platform: platform_address,
+            ledgers_to_extend,
+        }
+        .publish(e);
+
+        Ok(())
+    }
+
+    pub fn extend_dispute_statements_ttl(
+        e: &Env,
+        platform_address: Address,
+        start: u32,
+        limit: u32,
+        ledgers_to_extend: u32,
+    ) -> Result<(), ContractError> {
+        platform_address.require_auth();
+
+        let escrow = EscrowManager::get_escrow_header(e)?;
+        if platform_address != escrow.roles.platform_address {
+            return Err(ContractError::OnlyPlatformAddressExecuteThisFunction);
+        }
+
+        let min_ledgers = 1u32;
+        DisputeManager::extend_dispute_statements_ttl(
+            e,
+            start,
+            limit,
+            min_ledgers,
+            ledgers_to_extend,
+        );
+
+        ExtTtlEvt {
+            engagement_id: escrow.engagement_id,
             platform: platform_address,
             ledgers_to_extend,
         }
@@ -145,18 +320,25 @@ impl EscrowContract {
     pub fn change_milestone_status(
         e: Env,
         milestone_index: i128,
-        new_status: String,
-        new_evidence: Option<String>,
+        new_status: MilestoneStatus,
+        new_evidence: Option<EvidenceSubmission>,
         service_provider: Address,
     ) -> Result<(), ContractError> {
-        let escrow = MilestoneManager::change_milestone_status(
+        let (escrow, old_status) = MilestoneManager::change_milestone_status(
             &e,
             milestone_index,
             new_status,
             new_evidence,
-            service_provider,
+            service_provider.clone(),
         )?;
-        MilestoneStatusChanged { escrow }.publish(&e);
+        MilestoneStatusChanged {
+            engagement_id: escrow.engagement_id,
+            milestone_index,
+            old_status,
+            new_status,
+            actor: service_provider,
+        }
+        .publish(&e);
         Ok(())
     }
 
@@ -165,12 +347,80 @@ impl EscrowContract {
         milestone_index: i128,
         approver: Address,
     ) -> Result<(), ContractError> {
-        let escrow =
-            MilestoneManager::change_milestone_approved_flag(&e, milestone_index, approver)?;
-        MilestoneApproved { escrow }.publish(&e);
+        let (escrow, old_status) =
+            MilestoneManager::approve_milestone(&e, milestone_index, approver.clone())?;
+        MilestoneApproved {
+            engagement_id: escrow.engagement_id,
+            milestone_index,
+            old_status,
+            new_status: MilestoneStatus::Approved,
+            actor: approver,
+        }
+        .publish(&e);
+        Ok(())
+    }
+
+    pub fn request_milestone_changes(
+        e: Env,
+        milestone_index: i128,
+        approver: Address,
+        reason: String,
+    ) -> Result<(), ContractError> {
+        let (escrow, revisions) = MilestoneManager::request_milestone_changes(
+            &e,
+            milestone_index,
+            approver.clone(),
+            reason.clone(),
+        )?;
+        MilestoneChangesRequested {
+            engagement_id: escrow.engagement_id.clone(),
+            milestone_index,
+            revisions,
+            reason,
+        }
+        .publish(&e);
+        if escrow.status == EscrowStatus::Disputed {
+            EscrowDisputed {
+                engagement_id: escrow.engagement_id,
+                signer: approver,
+            }
+            .publish(&e);
+        }
         Ok(())
     }
 
+    pub fn expire_milestone(e: Env, milestone_index: i128) -> Result<(), ContractError> {
+        let (refunded_amount, plan) = MilestoneManager::expire_milestone(&e, milestone_index)?;
+        let escrow = EscrowManager::get_escrow_header(&e)?;
+        publish_payouts(&e, &escrow, &plan);
+        MilestoneExpired {
+            engagement_id: escrow.engagement_id,
+            milestone_index,
+            refunded_amount,
+        }
+        .publish(&e);
+        Ok(())
+    }
+
+    pub fn get_milestone(e: Env, milestone_index: u32) -> Result<Milestone, ContractError> {
+        MilestoneManager::get_milestone(&e, milestone_index)
+    }
+
+    pub fn get_milestone_evidence(
+        e: Env,
+        milestone_index: i128,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<EvidenceEntry>, ContractError> {
+        MilestoneManager::get_milestone(&e, milestone_index as u32)?;
+        Ok(EvidenceManager::get_milestone_evidence(
+            &e,
+            milestone_index as u32,
+            start,
+            limit,
+        ))
+    }
+
     ////////////////////////
     // Disputes /////
     ////////////////////////
@@ -178,22 +428,220 @@ impl EscrowContract {
     pub fn resolve_dispute(
         e: Env,
         dispute_resolver: Address,
-        trustless_work_address: Address,
         distributions: Map<Address, i128>,
     ) -> Result<(), ContractError> {
-        let escrow = DisputeManager::resolve_dispute(
+        resolve_and_publish(e, dispute_resolver, distributions, None)
+    }
+
+    pub fn resolve_dispute_by_shares(
+        e: Env,
+        dispute_resolver: Address,
+        shares: Map<Address, u32>,
+        dust_recipient: Address,
+    ) -> Result<(), ContractError> {
+        let share_split = ShareSplit {
+            shares,
+            dust_recipient,
+        };
+        let distributions = DisputeManager::share_distributions(&e, &share_split)?;
+        resolve_and_publish(e, dispute_resolver, distributions, Some(share_split))
+    }
+
+    pub fn quote_dispute_resolution(
+        e: Env,
+        distributions: Map<Address, i128>,
+    ) -> Result<Vec<PayoutTransfer>, ContractError> {
+        DisputeManager::quote_dispute_resolution(&e, distributions)
+    }
+
+    pub fn appeal_ruling(e: Env, signer: Address) -> Result<(), ContractError> {
+        let ruling = DisputeManager::appeal_ruling(&e, signer.clone())?;
+        RulingAppealed {
+            engagement_id: EscrowManager::get_escrow_header(&e)?.engagement_id,
+            signer,
+            ruling,
+        }
+        .publish(&e);
+        Ok(())
+    }
+
+    pub fn execute_ruling(e: Env) -> Result<(), ContractError> {
+        let (escrow, plan) = DisputeManager::execute_ruling(&e)?;
+        publish_payouts(&e, &escrow, &plan);
+        DisputeResolved {
+            engagement_id: escrow.engagement_id,
+            resolver: None,
+        }
+        .publish(&e);
+        Ok(())
+    }
+
+    pub fn get_ruling(e: Env) -> Option<Ruling> {
+        DisputeManager::get_ruling(&e)
+    }
+
+    pub fn get_dispute_proposals(e: Env) -> Map<Address, Map<Address, i128>> {
+        DisputeManager::get_dispute_proposals(&e)
+    }
+
+    pub fn get_milestone_dispute_proposals(
+        e: Env,
+        milestone_index: u32,
+    ) -> Map<Address, Map<Address, i128>> {
+        DisputeManager::get_milestone_dispute_proposals(&e, milestone_index)
+    }
+
+    pub fn dispute_escrow(
+        e: Env,
+        signer: Address,
+        reason: Option<String>,
+    ) -> Result<(), ContractError> {
+        let escrow = DisputeManager::dispute_escrow(&e, signer.clone(), reason)?;
+        EscrowDisputed {
+            engagement_id: escrow.engagement_id,
+            signer,
+        }
+        .publish(&e);
+        Ok(())
+    }
+
+    pub fn submit_dispute_statement(
+        e: Env,
+        signer: Address,
+        text_hash: BytesN<32>,
+        uri: String,
+    ) -> Result<(), ContractError> {
+        let statement_index =
+            DisputeManager::submit_dispute_statement(&e, signer.clone(), text_hash, uri)?;
+        DisputeStatementSubmitted {
+            engagement_id: EscrowManager::get_escrow_header(&e)?.engagement_id,
+            signer,
+            statement_index,
+        }
+        .publish(&e);
+        Ok(())
+    }
+
+    pub fn withdraw_dispute(e: Env, signer: Address) -> Result<(), ContractError> {
+        let escrow = DisputeManager::withdraw_dispute(&e, signer.clone())?;
+        DisputeWithdrawn {
+            engagement_id: escrow.engagement_id,
+            signer,
+            new_status: escrow.status,
+        }
+        .publish(&e);
+        Ok(())
+    }
+
+    pub fn finalize_stale_dispute(e: Env) -> Result<(), ContractError> {
+        let (escrow, plan) = DisputeManager::finalize_stale_dispute(&e)?;
+        publish_payouts(&e, &escrow, &plan);
+        StaleDisputeFinalized {
+            engagement_id: escrow.engagement_id,
+            fallback: escrow.dispute_fallback,
+        }
+        .publish(&e);
+        Ok(())
+    }
+
+    pub fn get_dispute_info(e: Env) -> Result<DisputeInfo, ContractError> {
+        DisputeManager::get_dispute_info(&e)
+    }
+
+    pub fn get_dispute_statements(e: Env, start: u32, limit: u32) -> Vec<DisputeStatement> {
+        DisputeManager::get_dispute_statements(&e, start, limit)
+    }
+
+    pub fn dispute_milestone(
+        e: Env,
+        milestone_index: i128,
+        signer: Address,
+    ) -> Result<(), ContractError> {
+        let escrow = DisputeManager::dispute_milestone(&e, milestone_index, signer.clone())?;
+        MilestoneDisputed {
+            engagement_id: escrow.engagement_id,
+            milestone_index,
+            signer,
+        }
+        .publish(&e);
+        Ok(())
+    }
+
+    pub fn resolve_milestone_dispute(
+        e: Env,
+        milestone_index: i128,
+        dispute_resolver: Address,
+        distributions: Map<Address, i128>,
+    ) -> Result<(), ContractError> {
+        let (escrow, approvals, outcome) = DisputeManager::resolve_milestone_dispute(
             &e,
-            dispute_resolver,
-            trustless_work_address,
-            distributions,
+            milestone_index,
+            dispute_resolver.clone(),
+            distributions.clone(),
         )?;
-        DisputeResolved { escrow }.publish(&e);
+        if let Some(approvals) = approvals {
+            MilestoneDisputeProposed {
+                engagement_id: escrow.engagement_id.clone(),
+                milestone_index,
+                resolver: dispute_resolver.clone(),
+                distributions,
+                approvals,
+            }
+            .publish(&e);
+        }
+        if let DisputeOutcome::Resolved(plan) = outcome {
+            publish_payouts(&e, &escrow, &plan);
+            MilestoneDisputeResolved {
+                engagement_id: escrow.engagement_id,
+                milestone_index,
+                resolver: dispute_resolver,
+                new_status: escrow.status,
+            }
+            .publish(&e);
+        }
         Ok(())
     }
+}
 
-    pub fn dispute_escrow(e: Env, signer: Address) -> Result<(), ContractError> {
-        let escrow = DisputeManager::dispute_escrow(&e, signer)?;
-        EscrowDisputed { escrow }.publish(&e);
-        Ok(())
+/// Shared by `resolve_dispute` and `resolve_dispute_by_shares`.
+fn resolve_and_publish(
+    e: Env,
+    dispute_resolver: Address,
+    distributions: Map<Address, i128>,
+    share_split: Option<ShareSplit>,
+) -> Result<(), ContractError> {
+    let (escrow, approvals, outcome) = DisputeManager::resolve_dispute(
+        &e,
+        dispute_resolver.clone(),
+        distributions.clone(),
+        share_split,
+    )?;
+    if let Some(approvals) = approvals {
+        DisputeProposalSubmitted {
+            engagement_id: escrow.engagement_id.clone(),
+            resolver: dispute_resolver.clone(),
+            distributions: distributions.clone(),
+            approvals,
+        }
+        .publish(&e);
+    }
+    match outcome {
+        DisputeOutcome::Voted => {}
+        DisputeOutcome::Ruled => DisputeRuled {
+            engagement_id: escrow.engagement_id,
+            resolver: dispute_resolver,
+            distributions,
+            ruled_at: e.ledger().timestamp(),
+        }
+        .publish(&e),
+        DisputeOutcome::Resolved(plan) => {
+            publish_payouts(&e, &escrow, &plan);
+            DisputeResolved {
+                engagement_id: escrow.engagement_id,
+                resolver: Some(dispute_resolver),
+            }
+            .publish(&e);
+        }
     }
+    Ok(())
 }
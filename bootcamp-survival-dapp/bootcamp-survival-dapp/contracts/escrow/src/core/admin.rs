@@ -0,0 +1,49 @@
+use soroban_sdk::{Address, Env};
+
+use crate::error::ContractError;
+use crate::modules::fee::{DEFAULT_TRUSTLESS_WORK_FEE_BPS, MAX_TRUSTLESS_WORK_FEE_BPS};
+use crate::storage::types::{DataKey, ProtocolConfig};
+
+pub struct AdminManager;
+
+impl AdminManager {
+    pub fn initialize(e: &Env, admin: Address, trustless_work_address: Address) {
+        let config = ProtocolConfig {
+            admin,
+            trustless_work_address,
+            trustless_work_fee: DEFAULT_TRUSTLESS_WORK_FEE_BPS,
+        };
+        e.storage().instance().set(&DataKey::Admin, &config);
+    }
+
+    pub fn get_protocol_config(e: &Env) -> ProtocolConfig {
+        // Always present: the constructor stores it at deployment.
+        e.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    /// Returns the previous rate. Escrows keep the rate they were created with.
+    pub fn set_trustless_work_fee(e: &Env, fee_bps: u32) -> Result<u32, ContractError> {
+        let mut config = Self::get_protocol_config(e);
+        config.admin.require_auth();
+
+        if fee_bps > MAX_TRUSTLESS_WORK_FEE_BPS {
+            return Err(ContractError::ProtocolFeeTooHigh);
+        }
+
+        let previous = config.trustless_work_fee;
+        config.trustless_work_fee = fee_bps;
+        e.storage().instance().set(&DataKey::Admin, &config);
+        Ok(previous)
+    }
+
+    /// Returns the previous fee recipient.
+    pub fn set_trustless_work_address(e: &Env, address: Address) -> Address {
+        let mut config = Self::get_protocol_config(e);
+        config.admin.require_auth();
+
+        let previous = config.trustless_work_address;
+        config.trustless_work_address = address;
+        e.storage().instance().set(&DataKey::Admin, &config);
+        previous
+    }
+}
@@ -1,94 +1,612 @@
 use soroban_sdk::token::Client as TokenClient;
-use soroban_sdk::{Address, Env, Map};
+use soroban_sdk::{Address, BytesN, Env, Map, String, Vec};
 
 use crate::core::escrow::EscrowManager;
+use crate::core::funding::FundingManager;
+use crate::core::milestone::MilestoneManager;
+use crate::core::payout::PayoutManager;
 use crate::error::ContractError;
-use crate::modules::{
-    fee::{FeeCalculator, FeeCalculatorTrait},
-    math::{BasicArithmetic, BasicMath},
+use crate::modules::math::{BasicArithmetic, BasicMath, Bps, Rounding, WideArithmetic, WideMath};
+use crate::storage::types::{
+    DataKey, DisputeFallback, DisputeInfo, DisputeStatement, Escrow, MilestoneStatus,
+    PayoutTransfer, Ruling,
 };
-use crate::storage::types::{DataKey, Escrow};
 
 use super::validators::dispute::{
+    validate_appeal_conditions, validate_appeal_resolution_conditions,
     validate_dispute_flag_change_conditions, validate_dispute_resolution_conditions,
+    validate_dispute_shares, validate_dispute_statement_conditions,
+    validate_dispute_withdrawal_conditions, validate_milestone_dispute_conditions,
+    validate_milestone_dispute_resolution_conditions, validate_ruling_execution_conditions,
+    validate_stale_dispute_conditions,
 };
+use super::validators::status::{validate_status_transition, EscrowAction};
+
+const MAX_STATEMENTS_PAGE: u32 = 50;
+
+/// What a `resolve_dispute` or `resolve_milestone_dispute` call ended up doing.
+pub enum DisputeOutcome {
+    /// A panel member voted but the threshold has not been reached yet.
+    Voted,
+    /// The ruling was stored and can be appealed until the window closes.
+    Ruled,
+    /// The distributions were paid out with these transfers and the escrow is resolved.
+    Resolved(Vec<PayoutTransfer>),
+}
+
+/// Basis-point shares of the escrow balance. Rounding dust goes to
+/// `dust_recipient`.
+#[derive(Clone)]
+pub struct ShareSplit {
+    pub shares: Map<Address, u32>,
+    pub dust_recipient: Address,
+}
 
 pub struct DisputeManager;
 
 impl DisputeManager {
+    /// `share_split` is kept with a two-phase ruling when `distributions` came
+    /// from `share_distributions`, so execution can split the balance held then.
     pub fn resolve_dispute(
         e: &Env,
         dispute_resolver: Address,
-        trustless_work_address: Address,
         distributions: Map<Address, i128>,
-    ) -> Result<Escrow, ContractError> {
+        share_split: Option<ShareSplit>,
+    ) -> Result<(Escrow, Option<u32>, DisputeOutcome), ContractError> {
         dispute_resolver.require_auth();
-        let mut escrow = EscrowManager::get_escrow(e)?;
+        let mut escrow = EscrowManager::get_escrow_header(e)?;
+        let next_status = validate_status_transition(escrow.status, EscrowAction::Resolve)?;
         let contract_address = e.current_contract_address();
 
-        let token_client = TokenClient::new(&e, &escrow.trustline.address);
+        let token_client = TokenClient::new(e, &escrow.trustline.address);
         let current_balance = token_client.balance(&contract_address);
 
-        let mut total: i128 = 0;
-        for (_addr, amount) in distributions.iter() {
-            if amount <= 0 {
-                return Err(ContractError::AmountsToBeTransferredShouldBePositive);
+        let total = Self::sum_distributions(&distributions)?;
+
+        let mut approvals = None;
+        let ruling = Self::get_ruling(e);
+        if ruling
+            .as_ref()
+            .is_some_and(|ruling| ruling.appealed_at.is_some())
+        {
+            // The appeal resolver's ruling is final and paid out right away.
+            validate_appeal_resolution_conditions(
+                &escrow,
+                &dispute_resolver,
+                current_balance,
+                total,
+            )?;
+        } else {
+            // A posted ruling can be appealed or executed, never replaced.
+            if ruling.is_some() {
+                return Err(ContractError::EscrowOpenedForDisputeResolution);
             }
-            total = BasicMath::safe_add(total, amount)?;
+            validate_dispute_resolution_conditions(
+                &escrow,
+                &dispute_resolver,
+                current_balance,
+                total,
+            )?;
+
+            // With a panel the proposal only goes through once enough members agree.
+            if !escrow.arbitration_panel.is_empty() {
+                let votes = Self::record_proposal(
+                    e,
+                    &DataKey::DisputeProposals,
+                    &dispute_resolver,
+                    &distributions,
+                );
+                approvals = Some(votes);
+                if votes < escrow.panel_threshold {
+                    return Ok((escrow, approvals, DisputeOutcome::Voted));
+                }
+                e.storage().instance().remove(&DataKey::DisputeProposals);
+            }
+
+            // In two-phase mode the ruling waits out the appeal window.
+            if escrow.appeal_window.is_some() {
+                let ruling = Ruling {
+                    distributions,
+                    ruled_at: e.ledger().timestamp(),
+                    appealed_at: None,
+                    shares: share_split
+                        .as_ref()
+                        .map_or(Map::new(e), |split| split.shares.clone()),
+                    dust_recipient: share_split.map(|split| split.dust_recipient),
+                };
+                e.storage().instance().set(&DataKey::Ruling, &ruling);
+                return Ok((escrow, approvals, DisputeOutcome::Ruled));
+            }
+        }
+
+        e.storage().instance().remove(&DataKey::Ruling);
+        let plan = Self::pay_distributions(e, &escrow, &distributions, total)?;
+        Self::ensure_fully_paid_out(e, &escrow)?;
+
+        escrow.status = next_status;
+        EscrowManager::set_escrow_header(e, &escrow);
+
+        Ok((escrow, approvals, DisputeOutcome::Resolved(plan)))
+    }
+
+    /// Turns basis-point shares into distributions of whatever balance the
+    /// escrow holds right now.
+    pub fn share_distributions(
+        e: &Env,
+        share_split: &ShareSplit,
+    ) -> Result<Map<Address, i128>, ContractError> {
+        let escrow = EscrowManager::get_escrow_header(e)?;
+        validate_dispute_shares(&share_split.shares)?;
+
+        let token_client = TokenClient::new(e, &escrow.trustline.address);
+        let current_balance = token_client.balance(&e.current_contract_address());
+        Self::split_balance(e, share_split, current_balance)
+    }
+
+    fn split_balance(
+        e: &Env,
+        share_split: &ShareSplit,
+        balance: i128,
+    ) -> Result<Map<Address, i128>, ContractError> {
+        let mut distributions: Map<Address, i128> = Map::new(e);
+        let mut allocated: i128 = 0;
+        for (addr, bps) in share_split.shares.iter() {
+            let amount = Bps::new(bps)?.apply(balance, Rounding::Floor)?;
+            if amount > 0 {
+                distributions.set(addr, amount);
+            }
+            allocated = BasicMath::safe_add(allocated, amount)?;
+        }
+
+        let dust = BasicMath::safe_sub(balance, allocated)?;
+        if dust > 0 {
+            let recipient = share_split.dust_recipient.clone();
+            let amount = distributions.get(recipient.clone()).unwrap_or(0);
+            distributions.set(recipient, BasicMath::safe_add(amount, dust)?);
         }
 
-        validate_dispute_resolution_conditions(
+        Ok(distributions)
+    }
+
+    /// Transfers `resolve_dispute` would make with `distributions` right now.
+    pub fn quote_dispute_resolution(
+        e: &Env,
+        distributions: Map<Address, i128>,
+    ) -> Result<Vec<PayoutTransfer>, ContractError> {
+        let escrow = EscrowManager::get_escrow_header(e)?;
+        validate_status_transition(escrow.status, EscrowAction::Resolve)?;
+
+        let token_client = TokenClient::new(e, &escrow.trustline.address);
+        let current_balance = token_client.balance(&e.current_contract_address());
+        let total = Self::sum_distributions(&distributions)?;
+        if total != current_balance {
+            return Err(ContractError::DistributionsMustEqualEscrowBalance);
+        }
+
+        PayoutManager::plan_distributions(e, &escrow, &distributions, total)
+    }
+
+    pub fn appeal_ruling(e: &Env, signer: Address) -> Result<Ruling, ContractError> {
+        signer.require_auth();
+        let escrow = EscrowManager::get_escrow_header(e)?;
+        validate_status_transition(escrow.status, EscrowAction::Resolve)?;
+
+        let mut ruling = Self::get_ruling(e).ok_or(ContractError::AppealNotAllowed)?;
+        validate_appeal_conditions(&escrow, &ruling, &signer, e.ledger().timestamp())?;
+
+        ruling.appealed_at = Some(e.ledger().timestamp());
+        e.storage().instance().set(&DataKey::Ruling, &ruling);
+
+        Ok(ruling)
+    }
+
+    /// Pays out a ruling whose appeal window closed without an appeal.
+    pub fn execute_ruling(e: &Env) -> Result<(Escrow, Vec<PayoutTransfer>), ContractError> {
+        let mut escrow = EscrowManager::get_escrow_header(e)?;
+        let next_status = validate_status_transition(escrow.status, EscrowAction::Resolve)?;
+        let mut ruling = Self::get_ruling(e).ok_or(ContractError::RulingNotExecutable)?;
+
+        let token_client = TokenClient::new(e, &escrow.trustline.address);
+        let current_balance = token_client.balance(&e.current_contract_address());
+        if let Some(dust_recipient) = ruling.dust_recipient.clone() {
+            let share_split = ShareSplit {
+                shares: ruling.shares.clone(),
+                dust_recipient,
+            };
+            ruling.distributions = Self::split_balance(e, &share_split, current_balance)?;
+        }
+        let total = Self::sum_distributions(&ruling.distributions)?;
+
+        validate_ruling_execution_conditions(
             &escrow,
-            &dispute_resolver,
+            &ruling,
+            e.ledger().timestamp(),
             current_balance,
             total,
         )?;
 
-        let fee_result = FeeCalculator::calculate_standard_fees(total, escrow.platform_fee)?;
-        let total_fees =
-            BasicMath::safe_add(fee_result.trustless_work_fee, fee_result.platform_fee)?;
+        e.storage().instance().remove(&DataKey::Ruling);
+        escrow.status = next_status;
+        EscrowManager::set_escrow_header(e, &escrow);
 
-        if fee_result.trustless_work_fee > 0 {
-            token_client.transfer(
-                &contract_address,
-                &trustless_work_address,
-                &fee_result.trustless_work_fee,
-            );
+        let mut plan = Self::pay_distributions(e, &escrow, &ruling.distributions, total)?;
+
+        // Tokens sent straight to the contract after the ruling go back to the funders
+        let surplus = BasicMath::safe_sub(current_balance, total)?;
+        if surplus > 0 {
+            let refunds = PayoutManager::plan_cancellation(e, &escrow, surplus)?;
+            PayoutManager::execute(e, &escrow, &refunds);
+            plan.append(&refunds);
         }
-        if fee_result.platform_fee > 0 {
-            token_client.transfer(
-                &contract_address,
-                &escrow.roles.platform_address,
-                &fee_result.platform_fee,
-            );
+        Self::ensure_fully_paid_out(e, &escrow)?;
+
+        Ok((escrow, plan))
+    }
+
+    pub fn get_ruling(e: &Env) -> Option<Ruling> {
+        e.storage().instance().get(&DataKey::Ruling)
+    }
+
+    /// Stores `distributions` as the member's current proposal under `key`,
+    /// replacing any earlier one, and returns how many members proposed exactly
+    /// the same split.
+    fn record_proposal(
+        e: &Env,
+        key: &DataKey,
+        member: &Address,
+        distributions: &Map<Address, i128>,
+    ) -> u32 {
+        let storage = e.storage().instance();
+        let mut proposals: Map<Address, Map<Address, i128>> =
+            storage.get(key).unwrap_or(Map::new(e));
+        proposals.set(member.clone(), distributions.clone());
+        storage.set(key, &proposals);
+
+        proposals
+            .values()
+            .iter()
+            .filter(|proposal| proposal == distributions)
+            .count() as u32
+    }
+
+    pub fn get_dispute_proposals(e: &Env) -> Map<Address, Map<Address, i128>> {
+        e.storage()
+            .instance()
+            .get(&DataKey::DisputeProposals)
+            .unwrap_or(Map::new(e))
+    }
+
+    pub fn get_milestone_dispute_proposals(
+        e: &Env,
+        milestone_index: u32,
+    ) -> Map<Address, Map<Address, i128>> {
+        e.storage()
+            .instance()
+            .get(&DataKey::MilestoneDisputeProposals(milestone_index))
+            .unwrap_or(Map::new(e))
+    }
+
+    pub fn dispute_escrow(
+        e: &Env,
+        signer: Address,
+        reason: Option<String>,
+    ) -> Result<Escrow, ContractError> {
+        signer.require_auth();
+        let mut escrow = EscrowManager::get_escrow_header(e)?;
+        let next_status = validate_status_transition(escrow.status, EscrowAction::Dispute)?;
+        validate_dispute_flag_change_conditions(&escrow, &signer)?;
+
+        escrow.status = next_status;
+        EscrowManager::set_escrow_header(e, &escrow);
+        Self::record_dispute_opened(e, &signer, reason);
+
+        Ok(escrow)
+    }
+
+    /// Stores the context of an escrow-wide dispute. Callers are expected to
+    /// have moved the escrow to `Disputed` already.
+    pub fn record_dispute_opened(e: &Env, opened_by: &Address, reason: Option<String>) {
+        let dispute = DisputeInfo {
+            opened_by: opened_by.clone(),
+            reason,
+            opened_at: e.ledger().timestamp(),
+        };
+        e.storage().instance().set(&DataKey::Dispute, &dispute);
+        e.storage().instance().remove(&DataKey::DisputeProposals);
+        e.storage().instance().remove(&DataKey::Ruling);
+    }
+
+    /// Takes the escrow back out of dispute so milestones and releases can
+    /// continue. Statements already submitted stay on record.
+    pub fn withdraw_dispute(e: &Env, signer: Address) -> Result<Escrow, ContractError> {
+        let mut escrow = EscrowManager::get_escrow_header(e)?;
+        let next_status = validate_status_transition(escrow.status, EscrowAction::WithdrawDispute)?;
+        let dispute = Self::get_dispute_info(e)?;
+        validate_dispute_withdrawal_conditions(
+            &escrow,
+            &dispute,
+            Self::get_ruling(e).as_ref(),
+            &Self::get_dispute_proposals(e),
+            &signer,
+        )?;
+
+        if signer == dispute.opened_by {
+            signer.require_auth();
+        } else {
+            escrow.roles.approver.require_auth();
+            escrow.roles.service_provider.require_auth();
         }
 
-        for (addr, amount) in distributions.iter() {
-            if amount <= 0 {
-                continue;
+        escrow.status = next_status;
+        EscrowManager::set_escrow_header(e, &escrow);
+        e.storage().instance().remove(&DataKey::Dispute);
+        e.storage().instance().remove(&DataKey::DisputeProposals);
+        e.storage().instance().remove(&DataKey::Ruling);
+
+        Ok(escrow)
+    }
+
+    /// Settles a dispute the resolver left untouched past the escrow's
+    /// resolution window, using the fallback chosen at initialization.
+    pub fn finalize_stale_dispute(e: &Env) -> Result<(Escrow, Vec<PayoutTransfer>), ContractError> {
+        let mut escrow = EscrowManager::get_escrow_header(e)?;
+        let next_status = validate_status_transition(escrow.status, EscrowAction::Resolve)?;
+        let dispute = Self::get_dispute_info(e)?;
+        validate_stale_dispute_conditions(
+            &escrow,
+            &dispute,
+            Self::get_ruling(e).as_ref(),
+            e.ledger().timestamp(),
+        )?;
+
+        let token_client = TokenClient::new(e, &escrow.trustline.address);
+        let current_balance = token_client.balance(&e.current_contract_address());
+        let distributions = Self::fallback_distributions(e, &escrow, current_balance)?;
+        let total = Self::sum_distributions(&distributions)?;
+
+        e.storage().instance().remove(&DataKey::Ruling);
+        escrow.status = next_status;
+        EscrowManager::set_escrow_header(e, &escrow);
+
+        let mut plan = Vec::new(e);
+        if total > 0 {
+            plan = Self::pay_distributions(e, &escrow, &distributions, total)?;
+            Self::ensure_fully_paid_out(e, &escrow)?;
+        }
+
+        Ok((escrow, plan))
+    }
+
+    fn fallback_distributions(
+        e: &Env,
+        escrow: &Escrow,
+        balance: i128,
+    ) -> Result<Map<Address, i128>, ContractError> {
+        match escrow.dispute_fallback {
+            DisputeFallback::RefundFunders => FundingManager::refund_shares(e, escrow, balance),
+            DisputeFallback::PayReceiver => {
+                let mut distributions: Map<Address, i128> = Map::new(e);
+                if balance > 0 {
+                    distributions.set(escrow.roles.receiver.clone(), balance);
+                }
+                Ok(distributions)
             }
-            let fee_share = (amount * (total_fees as i128)) / total;
-            let net_amount = amount - fee_share;
-            if net_amount > 0 {
-                token_client.transfer(&contract_address, &addr, &net_amount);
+            DisputeFallback::SplitByApprovedMilestones => {
+                let mut approved_amount: i128 = 0;
+                let mut pending_amount: i128 = 0;
+                for milestone in MilestoneManager::get_milestones(e).iter() {
+                    if milestone.released || milestone.expired {
+                        continue;
+                    }
+                    pending_amount = BasicMath::safe_add(pending_amount, milestone.amount)?;
+                    if milestone.status == MilestoneStatus::Approved {
+                        approved_amount = BasicMath::safe_add(approved_amount, milestone.amount)?;
+                    }
+                }
+
+                let receiver_amount = if pending_amount > 0 {
+                    // Neither side is favoured by the odd unit when nobody ruled.
+                    WideMath::mul_div(balance, approved_amount, pending_amount, Rounding::HalfEven)?
+                } else {
+                    0
+                };
+                let refund_amount = BasicMath::safe_sub(balance, receiver_amount)?;
+
+                let mut distributions = FundingManager::refund_shares(e, escrow, refund_amount)?;
+                if receiver_amount > 0 {
+                    let receiver = escrow.roles.receiver.clone();
+                    let existing = distributions.get(receiver.clone()).unwrap_or(0);
+                    distributions.set(receiver, BasicMath::safe_add(existing, receiver_amount)?);
+                }
+                Ok(distributions)
             }
         }
+    }
 
-        escrow.flags.resolved = true;
-        escrow.flags.disputed = false;
-        e.storage().instance().set(&DataKey::Escrow, &escrow);
+    pub fn get_dispute_info(e: &Env) -> Result<DisputeInfo, ContractError> {
+        e.storage()
+            .instance()
+            .get(&DataKey::Dispute)
+            .ok_or(ContractError::EscrowNotInDispute)
+    }
 
-        Ok(escrow)
+    pub fn submit_dispute_statement(
+        e: &Env,
+        signer: Address,
+        text_hash: BytesN<32>,
+        uri: String,
+    ) -> Result<u32, ContractError> {
+        signer.require_auth();
+        let escrow = EscrowManager::get_escrow_header(e)?;
+        validate_dispute_statement_conditions(&escrow, &signer)?;
+
+        let storage = e.storage().persistent();
+        let index = Self::get_dispute_statement_count(e);
+        let next_count = index.checked_add(1).ok_or(ContractError::Overflow)?;
+
+        let statement = DisputeStatement {
+            author: signer,
+            text_hash,
+            uri,
+            submitted_at: e.ledger().timestamp(),
+        };
+        storage.set(&DataKey::DisputeStatement(index), &statement);
+        storage.set(&DataKey::DisputeStatementCount, &next_count);
+
+        Ok(index)
+    }
+
+    pub fn get_dispute_statement_count(e: &Env) -> u32 {
+        e.storage()
+            .persistent()
+            .get(&DataKey::DisputeStatementCount)
+            .unwrap_or(0)
+    }
+
+    /// Returns up to `limit` statements, oldest first, starting at `start`.
+    pub fn get_dispute_statements(e: &Env, start: u32, limit: u32) -> Vec<DisputeStatement> {
+        let storage = e.storage().persistent();
+        let end = start
+            .saturating_add(limit.min(MAX_STATEMENTS_PAGE))
+            .min(Self::get_dispute_statement_count(e));
+
+        let mut statements: Vec<DisputeStatement> = Vec::new(e);
+        for index in start..end {
+            if let Some(statement) = storage.get(&DataKey::DisputeStatement(index)) {
+                statements.push_back(statement);
+            }
+        }
+        statements
+    }
+
+    /// Extends up to `limit` statements from `start`, capped at
+    /// `MAX_STATEMENTS_PAGE` like the reads.
+    pub fn extend_dispute_statements_ttl(
+        e: &Env,
+        start: u32,
+        limit: u32,
+        min_ledgers: u32,
+        ledgers_to_extend: u32,
+    ) {
+        let count = Self::get_dispute_statement_count(e);
+        if count == 0 {
+            return;
+        }
+        let storage = e.storage().persistent();
+        storage.extend_ttl(
+            &DataKey::DisputeStatementCount,
+            min_ledgers,
+            ledgers_to_extend,
+        );
+        let end = start
+            .saturating_add(limit.min(MAX_STATEMENTS_PAGE))
+            .min(count);
+        for index in start..end {
+            storage.extend_ttl(
+                &DataKey::DisputeStatement(index),
+                min_ledgers,
+                ledgers_to_extend,
+            );
+        }
     }
 
-    pub fn dispute_escrow(e: &Env, signer: Address) -> Result<Escrow, ContractError> {
+    /// Freezes a single milestone. The rest of the escrow keeps moving.
+    pub fn dispute_milestone(
+        e: &Env,
+        milestone_index: i128,
+        signer: Address,
+    ) -> Result<Escrow, ContractError> {
         signer.require_auth();
-        let mut escrow = EscrowManager::get_escrow(e)?;
-        validate_dispute_flag_change_conditions(&escrow, &signer)?;
+        let escrow = EscrowManager::get_escrow_header(e)?;
+        validate_status_transition(escrow.status, EscrowAction::SettleMilestone)?;
 
-        escrow.flags.disputed = true;
-        e.storage().instance().set(&DataKey::Escrow, &escrow);
+        let mut milestone = MilestoneManager::get_milestone(e, milestone_index as u32)?;
+        validate_milestone_dispute_conditions(&escrow, &milestone, &signer)?;
+
+        milestone.disputed = true;
+        MilestoneManager::set_milestone(e, milestone_index as u32, &milestone)?;
 
         Ok(escrow)
     }
+
+    /// Splits a disputed milestone's amount according to `distributions`, which
+    /// must add up to exactly that amount. The milestone is settled afterwards.
+    pub fn resolve_milestone_dispute(
+        e: &Env,
+        milestone_index: i128,
+        dispute_resolver: Address,
+        distributions: Map<Address, i128>,
+    ) -> Result<(Escrow, Option<u32>, DisputeOutcome), ContractError> {
+        dispute_resolver.require_auth();
+        let mut escrow = EscrowManager::get_escrow_header(e)?;
+        validate_status_transition(escrow.status, EscrowAction::SettleMilestone)?;
+
+        let index = milestone_index as u32;
+        let mut milestone = MilestoneManager::get_milestone(e, index)?;
+
+        let token_client = TokenClient::new(e, &escrow.trustline.address);
+        let current_balance = token_client.balance(&e.current_contract_address());
+        let total = Self::sum_distributions(&distributions)?;
+
+        validate_milestone_dispute_resolution_conditions(
+            &escrow,
+            &milestone,
+            &dispute_resolver,
+            current_balance,
+            total,
+        )?;
+
+        // A panel settles milestone disputes the same way it settles the escrow's.
+        let mut approvals = None;
+        if !escrow.arbitration_panel.is_empty() {
+            let key = DataKey::MilestoneDisputeProposals(index);
+            let votes = Self::record_proposal(e, &key, &dispute_resolver, &distributions);
+            approvals = Some(votes);
+            if votes < escrow.panel_threshold {
+                return Ok((escrow, approvals, DisputeOutcome::Voted));
+            }
+            e.storage().instance().remove(&key);
+        }
+
+        milestone.disputed = false;
+        milestone.released = true;
+        MilestoneManager::set_milestone(e, index, &milestone)?;
+        escrow.status = EscrowManager::settle_milestone_status(e, escrow.status)?;
+        EscrowManager::set_escrow_header(e, &escrow);
+
+        let plan = Self::pay_distributions(e, &escrow, &distributions, total)?;
+
+        Ok((escrow, approvals, DisputeOutcome::Resolved(plan)))
+    }
+
+    fn sum_distributions(distributions: &Map<Address, i128>) -> Result<i128, ContractError> {
+        let mut total: i128 = 0;
+        for (_addr, amount) in distributions.iter() {
+            if amount <= 0 {
+                return Err(ContractError::AmountsToBeTransferredShouldBePositive);
+            }
+            total = BasicMath::safe_add(total, amount)?;
+        }
+        Ok(total)
+    }
+
+    fn pay_distributions(
+        e: &Env,
+        escrow: &Escrow,
+        distributions: &Map<Address, i128>,
+        total: i128,
+    ) -> Result<Vec<PayoutTransfer>, ContractError> {
+        let plan = PayoutManager::plan_distributions(e, escrow, distributions, total)?;
+        PayoutManager::execute_with_fees(e, escrow, &plan, total)?;
+        Ok(plan)
+    }
+
+    /// A resolved escrow must not keep any funds behind.
+    fn ensure_fully_paid_out(e: &Env, escrow: &Escrow) -> Result<(), ContractError> {
+        let token_client = TokenClient::new(e, &escrow.trustline.address);
+        if token_client.balance(&e.current_contract_address()) != 0 {
+            return Err(ContractError::DistributionsMustEqualEscrowBalance);
+        }
+        Ok(())
+    }
 }
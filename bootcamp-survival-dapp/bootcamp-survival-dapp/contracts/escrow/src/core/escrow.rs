@@ -2,13 +2,18 @@
 use soroban_sdk::token::Client as TokenClient;
 use soroban_sdk::{Address, Env, Symbol, Vec};
 
+use crate::core::funding::FundingManager;
+use crate::core::milestone::MilestoneManager;
+use crate::core::payout::PayoutManager;
 use crate::core::validators::escrow::{
-    validate_escrow_property_change_conditions, validate_fund_escrow_conditions,
-    validate_initialize_escrow_conditions, validate_release_conditions,
+    validate_cancel_escrow_conditions, validate_escrow_property_change_conditions,
+    validate_fund_escrow_conditions, validate_initialize_escrow_conditions,
+    validate_milestone_release_conditions, validate_release_conditions,
 };
+use crate::core::validators::status::{validate_status_transition, EscrowAction};
 use crate::error::ContractError;
-use crate::modules::fee::{FeeCalculator, FeeCalculatorTrait};
-use crate::storage::types::{AddressBalance, DataKey, Escrow};
+use crate::modules::math::{BasicArithmetic, BasicMath};
+use crate::storage::types::{AddressBalance, DataKey, Escrow, EscrowStatus, PayoutTransfer};
 
 pub struct EscrowManager;
 
@@ -20,9 +25,8 @@ impl EscrowManager {
 
     pub fn initialize_escrow(e: &Env, escrow_properties: Escrow) -> Result<Escrow, ContractError> {
         validate_initialize_escrow_conditions(e, escrow_properties.clone())?;
-        e.storage()
-            .instance()
-            .set(&DataKey::Escrow, &escrow_properties);
+        Self::set_escrow_header(e, &escrow_properties);
+        MilestoneManager::save_milestones(e, &Vec::new(e), &escrow_properties.milestones);
         Ok(escrow_properties)
     }
 
@@ -32,54 +36,131 @@ impl EscrowManager {
         expected_escrow: &Escrow,
         amount: i128,
     ) -> Result<(), ContractError> {
-        let stored_escrow: Escrow = Self::get_escrow(e)?;
+        let mut stored_escrow: Escrow = Self::get_escrow(e)?;
+        let next_status = validate_status_transition(stored_escrow.status, EscrowAction::Fund)?;
         validate_fund_escrow_conditions(amount, &stored_escrow, expected_escrow)?;
 
         signer.require_auth();
         let token_client = TokenClient::new(e, &stored_escrow.trustline.address);
-        token_client.transfer(signer, &e.current_contract_address(), &amount);
+        token_client.transfer(signer, e.current_contract_address(), &amount);
+        FundingManager::record_contribution(e, &stored_escrow, signer, amount)?;
+
+        if next_status != stored_escrow.status {
+            stored_escrow.status = next_status;
+            Self::set_escrow_header(e, &stored_escrow);
+        }
         Ok(())
     }
 
     pub fn release_funds(
         e: &Env,
         release_signer: &Address,
-        trustless_work_address: &Address,
-    ) -> Result<(), ContractError> {
+    ) -> Result<Vec<PayoutTransfer>, ContractError> {
         release_signer.require_auth();
 
         let mut escrow = Self::get_escrow(e)?;
+        let next_status = validate_status_transition(escrow.status, EscrowAction::Release)?;
         validate_release_conditions(&escrow, release_signer)?;
 
-        escrow.flags.released = true;
-        e.storage().instance().set(&DataKey::Escrow, &escrow);
+        let pending_amount = Self::pending_release_amount(&escrow)?;
+        MilestoneManager::release_all(e);
+        escrow.status = next_status;
+        Self::set_escrow_header(e, &escrow);
 
-        let contract_address = e.current_contract_address();
-        let token_client = TokenClient::new(e, &escrow.trustline.address);
+        Self::pay_out(e, &escrow, pending_amount)
+    }
+
+    /// Transfers `release_funds` would make right now, failing with the same
+    /// errors it would. Only the release signer's authorization is skipped.
+    pub fn quote_release(e: &Env) -> Result<Vec<PayoutTransfer>, ContractError> {
+        let escrow = Self::get_escrow(e)?;
+        validate_status_transition(escrow.status, EscrowAction::Release)?;
+        validate_release_conditions(&escrow, &escrow.roles.release_signer)?;
+        let pending_amount = Self::pending_release_amount(&escrow)?;
+        PayoutManager::plan_release(e, &escrow, pending_amount)
+    }
+
+    pub fn release_milestone_funds(
+        e: &Env,
+        milestone_index: i128,
+        release_signer: &Address,
+    ) -> Result<Vec<PayoutTransfer>, ContractError> {
+        release_signer.require_auth();
+
+        let mut escrow = Self::get_escrow_header(e)?;
+        let mut milestone = MilestoneManager::get_milestone(e, milestone_index as u32)?;
+        validate_milestone_release_conditions(&escrow, &milestone, release_signer)?;
+
+        milestone.released = true;
+        let milestone_amount = milestone.amount;
+        MilestoneManager::set_milestone(e, milestone_index as u32, &milestone)?;
+        escrow.status = Self::settle_milestone_status(e, escrow.status)?;
+        Self::set_escrow_header(e, &escrow);
+
+        Self::pay_out(e, &escrow, milestone_amount)
+    }
 
-        if token_client.balance(&contract_address) < escrow.amount {
-            return Err(ContractError::EscrowBalanceNotEnoughToSendEarnings);
+    pub fn cancel_escrow(
+        e: &Env,
+        signer: &Address,
+    ) -> Result<(i128, Vec<PayoutTransfer>), ContractError> {
+        let mut escrow = Self::get_escrow(e)?;
+        let next_status = validate_status_transition(escrow.status, EscrowAction::Cancel)?;
+        validate_cancel_escrow_conditions(&escrow, signer)?;
+
+        if signer == &escrow.roles.platform_address {
+            signer.require_auth();
+        } else {
+            escrow.roles.approver.require_auth();
+            escrow.roles.service_provider.require_auth();
         }
 
-        let fee_result =
-            FeeCalculator::calculate_standard_fees(escrow.amount as i128, escrow.platform_fee)?;
+        escrow.status = next_status;
+        Self::set_escrow_header(e, &escrow);
 
-        token_client.transfer(
-            &contract_address,
-            trustless_work_address,
-            &fee_result.trustless_work_fee,
-        );
-        token_client.transfer(
-            &contract_address,
-            &escrow.roles.platform_address,
-            &fee_result.platform_fee,
-        );
+        let token_client = TokenClient::new(e, &escrow.trustline.address);
+        let balance = token_client.balance(&e.current_contract_address());
+        let plan = PayoutManager::plan_cancellation(e, &escrow, balance)?;
+        PayoutManager::execute(e, &escrow, &plan);
 
-        let receiver = Self::get_receiver(&escrow);
-        token_client.transfer(&contract_address, &receiver, &fee_result.receiver_amount);
+        Ok((balance, plan))
+    }
 
-        Ok(())
+    /// Status after a single milestone was paid out or refunded: the escrow is
+    /// released once every milestone has been settled.
+    pub fn settle_milestone_status(
+        e: &Env,
+        status: EscrowStatus,
+    ) -> Result<EscrowStatus, ContractError> {
+        let header = MilestoneManager::get_header(e);
+        let action = if header.settled == header.count {
+            EscrowAction::Release
+        } else {
+            EscrowAction::SettleMilestone
+        };
+        validate_status_transition(status, action)
+    }
+
+    fn pending_release_amount(escrow: &Escrow) -> Result<i128, ContractError> {
+        let mut pending_amount: i128 = 0;
+        for milestone in escrow.milestones.iter() {
+            if !milestone.released && !milestone.expired {
+                pending_amount = BasicMath::safe_add(pending_amount, milestone.amount)?;
+            }
+        }
+        Ok(pending_amount)
+    }
+
+    fn pay_out(
+        e: &Env,
+        escrow: &Escrow,
+        amount: i128,
+    ) -> Result<Vec<PayoutTransfer>, ContractError> {
+        let plan = PayoutManager::plan_release(e, escrow, amount)?;
+        PayoutManager::execute_with_fees(e, escrow, &plan, amount)?;
+        Ok(plan)
     }
+
     pub fn change_escrow_properties(
         e: &Env,
         platform_address: &Address,
@@ -87,6 +168,7 @@ impl EscrowManager {
     ) -> Result<Escrow, ContractError> {
         platform_address.require_auth();
         let existing_escrow = Self::get_escrow(e)?;
+        validate_status_transition(existing_escrow.status, EscrowAction::UpdateProperties)?;
         let token_client = TokenClient::new(e, &existing_escrow.trustline.address);
         let contract_balance = token_client.balance(&e.current_contract_address());
 
@@ -97,9 +179,12 @@ impl EscrowManager {
             contract_balance,
         )?;
 
-        e.storage()
-            .instance()
-            .set(&DataKey::Escrow, &escrow_properties);
+        Self::set_escrow_header(e, &escrow_properties);
+        MilestoneManager::save_milestones(
+            e,
+            &existing_escrow.milestones,
+            &escrow_properties.milestones,
+        );
         Ok(escrow_properties)
     }
 
@@ -116,7 +201,7 @@ impl EscrowManager {
         let self_addr = e.current_contract_address();
         for address in addresses.iter() {
             let escrow = if address == self_addr {
-                Self::get_escrow(e)?
+                Self::get_escrow_header(e)?
             } else {
                 Self::get_escrow_by_contract_id(e, &address)?
             };
@@ -138,10 +223,29 @@ impl EscrowManager {
         Ok(e.invoke_contract::<Escrow>(contract_id, &Symbol::new(e, "get_escrow"), Vec::new(e)))
     }
 
+    pub fn get_status(e: &Env) -> Result<EscrowStatus, ContractError> {
+        Ok(Self::get_escrow_header(e)?.status)
+    }
+
     pub fn get_escrow(e: &Env) -> Result<Escrow, ContractError> {
-        Ok(e.storage()
+        let mut escrow = Self::get_escrow_header(e)?;
+        escrow.milestones = MilestoneManager::get_milestones(e);
+        Ok(escrow)
+    }
+
+    /// The escrow as stored, without its milestones. Paths that touch a single
+    /// milestone read it with `MilestoneManager::get_milestone` instead.
+    pub fn get_escrow_header(e: &Env) -> Result<Escrow, ContractError> {
+        e.storage()
             .instance()
             .get(&DataKey::Escrow)
-            .ok_or(ContractError::EscrowNotFound)?)
+            .ok_or(ContractError::EscrowNotFound)
+    }
+
+    /// Stores everything but the milestones, which live in their own entries.
+    pub fn set_escrow_header(e: &Env, escrow: &Escrow) {
+        let mut header = escrow.clone();
+        header.milestones = Vec::new(e);
+        e.storage().instance().set(&DataKey::Escrow, &header);
     }
 }
@@ -0,0 +1,94 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::error::ContractError;
+use crate::storage::types::{DataKey, EvidenceEntry, EvidenceSubmission};
+
+const MAX_EVIDENCE_PAGE: u32 = 50;
+
+pub struct EvidenceManager;
+
+impl EvidenceManager {
+    pub fn record_evidence(
+        e: &Env,
+        milestone_index: u32,
+        submission: EvidenceSubmission,
+        submitter: &Address,
+    ) -> Result<EvidenceEntry, ContractError> {
+        let storage = e.storage().persistent();
+        let count = Self::get_evidence_count(e, milestone_index);
+        let next_count = count.checked_add(1).ok_or(ContractError::Overflow)?;
+
+        let entry = EvidenceEntry {
+            hash: submission.hash,
+            uri: submission.uri,
+            submitter: submitter.clone(),
+            submitted_at: e.ledger().timestamp(),
+        };
+
+        storage.set(&DataKey::Evidence(milestone_index, count), &entry);
+        storage.set(&DataKey::EvidenceCount(milestone_index), &next_count);
+        Ok(entry)
+    }
+
+    pub fn get_evidence_count(e: &Env, milestone_index: u32) -> u32 {
+        e.storage()
+            .persistent()
+            .get(&DataKey::EvidenceCount(milestone_index))
+            .unwrap_or(0)
+    }
+
+    /// Returns up to `limit` entries of the milestone's evidence log, oldest
+    /// first, starting at `start`. Pages are capped at `MAX_EVIDENCE_PAGE`.
+    pub fn get_milestone_evidence(
+        e: &Env,
+        milestone_index: u32,
+        start: u32,
+        limit: u32,
+    ) -> Vec<EvidenceEntry> {
+        let storage = e.storage().persistent();
+        let count = Self::get_evidence_count(e, milestone_index);
+        let end = start
+            .saturating_add(limit.min(MAX_EVIDENCE_PAGE))
+            .min(count);
+
+        let mut entries: Vec<EvidenceEntry> = Vec::new(e);
+        for index in start..end {
+            if let Some(entry) = storage.get(&DataKey::Evidence(milestone_index, index)) {
+                entries.push_back(entry);
+            }
+        }
+        entries
+    }
+
+    /// Extends one page of the milestone's evidence log, paged like
+    /// `get_milestone_evidence` so a long log never has to fit one transaction.
+    pub fn extend_evidence_ttl(
+        e: &Env,
+        milestone_index: u32,
+        start: u32,
+        limit: u32,
+        min_ledgers: u32,
+        ledgers_to_extend: u32,
+    ) {
+        let count = Self::get_evidence_count(e, milestone_index);
+        if count == 0 {
+            return;
+        }
+        let storage = e.storage().persistent();
+        storage.extend_ttl(
+            &DataKey::EvidenceCount(milestone_index),
+            min_ledgers,
+            ledgers_to_extend,
+        );
+        let end = start
+            .saturating_add(limit.min(MAX_EVIDENCE_PAGE))
+            .min(count);
+        for index in start..end {
+            storage.extend_ttl(
+                &DataKey::Evidence(milestone_index, index),
+                min_ledgers,
+                ledgers_to_extend,
+            );
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use soroban_sdk::{Address, Env, Map, Vec};
+
+use crate::error::ContractError;
+use crate::modules::math::{BasicArithmetic, BasicMath, Rounding, WideArithmetic, WideMath};
+use crate::storage::types::{Contribution, DataKey, Escrow};
+
+const MAX_FUNDERS: u32 = 20;
+
+pub struct FundingManager;
+
+impl FundingManager {
+    /// A new funder has to bring at least `amount / MAX_FUNDERS`, so the funder
+    /// slots cannot be used up with dust.
+    pub fn record_contribution(
+        e: &Env,
+        escrow: &Escrow,
+        funder: &Address,
+        amount: i128,
+    ) -> Result<Contribution, ContractError> {
+        let storage = e.storage().persistent();
+        let now = e.ledger().timestamp();
+        let key = DataKey::Contribution(funder.clone());
+
+        let contribution = match storage.get::<_, Contribution>(&key) {
+            Some(mut existing) => {
+                existing.amount = BasicMath::safe_add(existing.amount, amount)?;
+                existing.last_funded_at = now;
+                existing
+            }
+            None => {
+                // A dust deposit would take a funder slot just as a real one does.
+                let mut funders = Self::get_funders(e);
+                if funders.len() >= MAX_FUNDERS || amount < Self::min_first_contribution(escrow)? {
+                    return Err(ContractError::TooManyFunders);
+                }
+                funders.push_back(funder.clone());
+                storage.set(&DataKey::Funders, &funders);
+                Contribution {
+                    funder: funder.clone(),
+                    amount,
+                    first_funded_at: now,
+                    last_funded_at: now,
+                }
+            }
+        };
+
+        storage.set(&key, &contribution);
+        Ok(contribution)
+    }
+
+    fn min_first_contribution(escrow: &Escrow) -> Result<i128, ContractError> {
+        WideMath::mul_div(escrow.amount, 1, MAX_FUNDERS as i128, Rounding::Ceil)
+    }
+
+    pub fn get_funders(e: &Env) -> Vec<Address> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::Funders)
+            .unwrap_or(Vec::new(e))
+    }
+
+    pub fn get_contributions(e: &Env) -> Vec<Contribution> {
+        let storage = e.storage().persistent();
+        let mut contributions: Vec<Contribution> = Vec::new(e);
+        for funder in Self::get_funders(e).iter() {
+            if let Some(contribution) = storage.get(&DataKey::Contribution(funder)) {
+                contributions.push_back(contribution);
+            }
+        }
+        contributions
+    }
+
+    /// Splits `amount` across the funders in proportion to what each of them
+    /// contributed. The last funder absorbs the rounding remainder. Escrows
+    /// funded outside `fund_escrow` have no records, so the approver gets it all.
+    pub fn refund_shares(
+        e: &Env,
+        escrow: &Escrow,
+        amount: i128,
+    ) -> Result<Map<Address, i128>, ContractError> {
+        let mut shares: Map<Address, i128> = Map::new(e);
+        if amount <= 0 {
+            return Ok(shares);
+        }
+
+        let contributions = Self::get_contributions(e);
+
+        let mut total_contributed: i128 = 0;
+        for contribution in contributions.iter() {
+            total_contributed = BasicMath::safe_add(total_contributed, contribution.amount)?;
+        }
+
+        if total_contributed <= 0 {
+            shares.set(escrow.roles.approver.clone(), amount);
+            return Ok(shares);
+        }
+
+        let mut remaining = amount;
+        let last_index = contributions.len() - 1;
+        for (index, contribution) in contributions.iter().enumerate() {
+            let share = if index as u32 == last_index {
+                remaining
+            } else {
+                WideMath::mul_div(
+                    amount,
+                    contribution.amount,
+                    total_contributed,
+                    Rounding::Floor,
+                )?
+            };
+            remaining = BasicMath::safe_sub(remaining, share)?;
+            if share > 0 {
+                shares.set(contribution.funder, share);
+            }
+        }
+
+        Ok(shares)
+    }
+
+    pub fn extend_contributions_ttl(e: &Env, min_ledgers: u32, ledgers_to_extend: u32) {
+        let storage = e.storage().persistent();
+        if !storage.has(&DataKey::Funders) {
+            return;
+        }
+        storage.extend_ttl(&DataKey::Funders, min_ledgers, ledgers_to_extend);
+        for funder in Self::get_funders(e).iter() {
+            storage.extend_ttl(
+                &DataKey::Contribution(funder),
+                min_ledgers,
+                ledgers_to_extend,
+            );
+        }
+    }
+}
@@ -1,12 +1,24 @@
 // Arjun edit: added milestone design note for bootcamp demo
+use crate::core::dispute::DisputeManager;
+use crate::core::evidence::EvidenceManager;
+use crate::core::payout::PayoutManager;
 use crate::error::ContractError;
-use crate::storage::types::DataKey;
+use crate::storage::types::{
+    DataKey, EvidenceSubmission, Milestone, MilestoneHeader, MilestoneStatus, PayoutTransfer,
+};
 use crate::{core::escrow::EscrowManager, storage::types::Escrow};
-use soroban_sdk::{Address, Env, String};
+use soroban_sdk::{Address, Env, String, Vec};
 
 use super::validators::milestone::{
+    validate_milestone_changes_request_conditions, validate_milestone_expiry_conditions,
     validate_milestone_flag_change_conditions, validate_milestone_status_change_conditions,
 };
+use super::validators::status::{validate_status_transition, EscrowAction};
+
+const DAY_IN_LEDGERS: u32 = 17_280;
+/// Milestone entries are bumped on write once they are this close to expiring.
+const MILESTONE_TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+const MILESTONE_TTL_EXTEND_TO: u32 = 120 * DAY_IN_LEDGERS;
 
 pub struct MilestoneManager;
 
@@ -14,63 +26,258 @@ impl MilestoneManager {
     pub fn change_milestone_status(
         e: &Env,
         milestone_index: i128,
-        new_status: String,
-        new_evidence: Option<String>,
+        new_status: MilestoneStatus,
+        new_evidence: Option<EvidenceSubmission>,
         service_provider: Address,
-    ) -> Result<Escrow, ContractError> {
+    ) -> Result<(Escrow, MilestoneStatus), ContractError> {
         service_provider.require_auth();
-        let mut existing_escrow = EscrowManager::get_escrow(e)?;
+        let mut existing_escrow = EscrowManager::get_escrow_header(e)?;
+        let next_status =
+            validate_status_transition(existing_escrow.status, EscrowAction::UpdateMilestone)?;
 
-        validate_milestone_status_change_conditions(&existing_escrow, &service_provider)?;
+        let mut milestone_to_update = Self::get_milestone(e, milestone_index as u32)?;
 
-        let mut milestone_to_update = existing_escrow
-            .milestones
-            .get(milestone_index as u32)
-            .ok_or(ContractError::InvalidMileStoneIndex)?;
+        validate_milestone_status_change_conditions(
+            &existing_escrow,
+            &milestone_to_update,
+            new_status,
+            &service_provider,
+        )?;
 
         if let Some(evidence) = new_evidence {
-            milestone_to_update.evidence = evidence;
+            milestone_to_update.evidence = evidence.uri.clone();
+            EvidenceManager::record_evidence(
+                e,
+                milestone_index as u32,
+                evidence,
+                &service_provider,
+            )?;
         }
 
+        let old_status = milestone_to_update.status;
         milestone_to_update.status = new_status;
+        Self::set_milestone(e, milestone_index as u32, &milestone_to_update)?;
+        if next_status != existing_escrow.status {
+            existing_escrow.status = next_status;
+            EscrowManager::set_escrow_header(e, &existing_escrow);
+        }
 
-        existing_escrow
-            .milestones
-            .set(milestone_index as u32, milestone_to_update);
-        e.storage()
-            .instance()
-            .set(&DataKey::Escrow, &existing_escrow);
-
-        Ok(existing_escrow)
+        Ok((existing_escrow, old_status))
     }
 
-    pub fn change_milestone_approved_flag(
+    pub fn approve_milestone(
         e: &Env,
         milestone_index: i128,
         approver: Address,
-    ) -> Result<Escrow, ContractError> {
+    ) -> Result<(Escrow, MilestoneStatus), ContractError> {
         approver.require_auth();
-        let mut existing_escrow = EscrowManager::get_escrow(e)?;
+        let mut existing_escrow = EscrowManager::get_escrow_header(e)?;
+        let next_status =
+            validate_status_transition(existing_escrow.status, EscrowAction::UpdateMilestone)?;
 
-        let mut milestone_to_update = existing_escrow
-            .milestones
-            .get(milestone_index as u32)
-            .ok_or(ContractError::InvalidMileStoneIndex)?;
+        let mut milestone_to_update = Self::get_milestone(e, milestone_index as u32)?;
 
         validate_milestone_flag_change_conditions(
             &existing_escrow,
             &milestone_to_update,
             &approver,
         )?;
-        milestone_to_update.approved = true;
+        let old_status = milestone_to_update.status;
+        milestone_to_update.status = MilestoneStatus::Approved;
+        Self::set_milestone(e, milestone_index as u32, &milestone_to_update)?;
+        if next_status != existing_escrow.status {
+            existing_escrow.status = next_status;
+            EscrowManager::set_escrow_header(e, &existing_escrow);
+        }
+
+        Ok((existing_escrow, old_status))
+    }
+
+    /// Sends a submitted milestone back to the service provider. Once the
+    /// escrow's `max_revisions` is exceeded the milestone is rejected and the
+    /// escrow is opened for dispute instead.
+    pub fn request_milestone_changes(
+        e: &Env,
+        milestone_index: i128,
+        approver: Address,
+        reason: String,
+    ) -> Result<(Escrow, u32), ContractError> {
+        approver.require_auth();
+        let mut existing_escrow = EscrowManager::get_escrow_header(e)?;
+        let mut next_status =
+            validate_status_transition(existing_escrow.status, EscrowAction::UpdateMilestone)?;
+
+        let mut milestone_to_update = Self::get_milestone(e, milestone_index as u32)?;
+
+        validate_milestone_changes_request_conditions(
+            &existing_escrow,
+            &milestone_to_update,
+            &approver,
+        )?;
+
+        let revisions = milestone_to_update
+            .revisions
+            .checked_add(1)
+            .ok_or(ContractError::Overflow)?;
+        milestone_to_update.revisions = revisions;
+        milestone_to_update.revision_reason = Some(reason.clone());
+        milestone_to_update.status = MilestoneStatus::ChangesRequested;
+
+        if let Some(max_revisions) = existing_escrow.max_revisions {
+            if revisions > max_revisions {
+                milestone_to_update.status = MilestoneStatus::Rejected;
+                next_status = validate_status_transition(next_status, EscrowAction::Dispute)?;
+                DisputeManager::record_dispute_opened(e, &approver, Some(reason));
+            }
+        }
+        Self::set_milestone(e, milestone_index as u32, &milestone_to_update)?;
+        if next_status != existing_escrow.status {
+            existing_escrow.status = next_status;
+            EscrowManager::set_escrow_header(e, &existing_escrow);
+        }
+
+        Ok((existing_escrow, revisions))
+    }
+
+    pub fn expire_milestone(
+        e: &Env,
+        milestone_index: i128,
+    ) -> Result<(i128, Vec<PayoutTransfer>), ContractError> {
+        let mut existing_escrow = EscrowManager::get_escrow_header(e)?;
+        validate_status_transition(existing_escrow.status, EscrowAction::SettleMilestone)?;
+
+        let mut milestone_to_expire = Self::get_milestone(e, milestone_index as u32)?;
+
+        validate_milestone_expiry_conditions(&milestone_to_expire, e.ledger().timestamp())?;
+
+        milestone_to_expire.expired = true;
+        let milestone_amount = milestone_to_expire.amount;
+        Self::set_milestone(e, milestone_index as u32, &milestone_to_expire)?;
+        existing_escrow.status = EscrowManager::settle_milestone_status(e, existing_escrow.status)?;
+        EscrowManager::set_escrow_header(e, &existing_escrow);
+
+        let (plan, refunded_amount) =
+            PayoutManager::plan_refund(e, &existing_escrow, milestone_amount)?;
+        PayoutManager::execute_with_fees(e, &existing_escrow, &plan, milestone_amount)?;
+
+        Ok((refunded_amount, plan))
+    }
+
+    pub fn get_header(e: &Env) -> MilestoneHeader {
+        e.storage()
+            .instance()
+            .get(&DataKey::MilestoneHeader)
+            .unwrap_or(MilestoneHeader {
+                count: 0,
+                settled: 0,
+                released_all: false,
+            })
+    }
+
+    pub fn get_milestone(e: &Env, index: u32) -> Result<Milestone, ContractError> {
+        let milestone = e
+            .storage()
+            .persistent()
+            .get(&DataKey::Milestone(index))
+            .ok_or(ContractError::InvalidMileStoneIndex)?;
+        Ok(Self::with_header(&Self::get_header(e), milestone))
+    }
+
+    pub fn get_milestones(e: &Env) -> Vec<Milestone> {
+        let storage = e.storage().persistent();
+        let header = Self::get_header(e);
+        let mut milestones: Vec<Milestone> = Vec::new(e);
+        for index in 0..header.count {
+            if let Some(milestone) = storage.get(&DataKey::Milestone(index)) {
+                milestones.push_back(Self::with_header(&header, milestone));
+            }
+        }
+        milestones
+    }
 
-        existing_escrow
-            .milestones
-            .set(milestone_index as u32, milestone_to_update);
+    /// Marks every milestone that is not settled yet as released with a single
+    /// header write, however many milestones the escrow has.
+    pub fn release_all(e: &Env) {
+        let mut header = Self::get_header(e);
+        header.settled = header.count;
+        header.released_all = true;
         e.storage()
             .instance()
-            .set(&DataKey::Escrow, &existing_escrow);
+            .set(&DataKey::MilestoneHeader, &header);
+    }
+
+    /// Overwrites one stored milestone and keeps the header's settled count
+    /// in step with it.
+    pub fn set_milestone(e: &Env, index: u32, milestone: &Milestone) -> Result<(), ContractError> {
+        let previous = Self::get_milestone(e, index)?;
+        if Self::is_settled(&previous) != Self::is_settled(milestone) {
+            let mut header = Self::get_header(e);
+            header.settled = if Self::is_settled(milestone) {
+                header
+                    .settled
+                    .checked_add(1)
+                    .ok_or(ContractError::Overflow)?
+            } else {
+                header
+                    .settled
+                    .checked_sub(1)
+                    .ok_or(ContractError::Underflow)?
+            };
+            e.storage()
+                .instance()
+                .set(&DataKey::MilestoneHeader, &header);
+        }
+        Self::write_milestone(e, index, milestone);
+        Ok(())
+    }
+
+    /// Replaces the stored milestones, writing only the entries that differ
+    /// from `previous` and dropping any past the new end.
+    pub fn save_milestones(e: &Env, previous: &Vec<Milestone>, milestones: &Vec<Milestone>) {
+        let mut settled: u32 = 0;
+        for (index, milestone) in milestones.iter().enumerate() {
+            if Self::is_settled(&milestone) {
+                settled += 1;
+            }
+            if previous.get(index as u32).as_ref() != Some(&milestone) {
+                Self::write_milestone(e, index as u32, &milestone);
+            }
+        }
+        for index in milestones.len()..previous.len() {
+            e.storage().persistent().remove(&DataKey::Milestone(index));
+        }
+        e.storage().instance().set(
+            &DataKey::MilestoneHeader,
+            &MilestoneHeader {
+                count: milestones.len(),
+                settled,
+                released_all: false,
+            },
+        );
+    }
+
+    pub fn extend_milestones_ttl(e: &Env, min_ledgers: u32, ledgers_to_extend: u32) {
+        let storage = e.storage().persistent();
+        for index in 0..Self::get_header(e).count {
+            storage.extend_ttl(&DataKey::Milestone(index), min_ledgers, ledgers_to_extend);
+        }
+    }
+
+    fn write_milestone(e: &Env, index: u32, milestone: &Milestone) {
+        let storage = e.storage().persistent();
+        let key = DataKey::Milestone(index);
+        storage.set(&key, milestone);
+        storage.extend_ttl(&key, MILESTONE_TTL_THRESHOLD, MILESTONE_TTL_EXTEND_TO);
+    }
+
+    fn with_header(header: &MilestoneHeader, mut milestone: Milestone) -> Milestone {
+        if header.released_all && !milestone.expired {
+            milestone.released = true;
+        }
+        milestone
+    }
 
-        Ok(existing_escrow)
+    fn is_settled(milestone: &Milestone) -> bool {
+        milestone.released || milestone.expired
     }
 }
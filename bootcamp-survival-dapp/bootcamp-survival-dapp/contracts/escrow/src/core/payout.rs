@@ -0,0 +1,236 @@
+use soroban_sdk::token::Client as TokenClient;
+use soroban_sdk::{Address, Env, Map, Vec};
+
+use crate::core::admin::AdminManager;
+use crate::core::escrow::EscrowManager;
+use crate::core::funding::FundingManager;
+use crate::error::ContractError;
+use crate::modules::{
+    fee::{FeeCalculator, FeeCalculatorTrait, StandardFeeResult},
+    math::{BasicArithmetic, BasicMath, Bps, Rounding, WideArithmetic, WideMath},
+};
+use crate::storage::types::{DataKey, Escrow, FeeTotals, PayoutReason, PayoutTransfer};
+
+/// Builds the exact transfers of a payout. Releases, dispute resolutions and
+/// the quote entrypoints all go through these plans, so a quote always
+/// matches what would be paid.
+pub struct PayoutManager;
+
+impl PayoutManager {
+    /// Fees and receiver transfer for releasing `amount` to the receiver.
+    pub fn plan_release(
+        e: &Env,
+        escrow: &Escrow,
+        amount: i128,
+    ) -> Result<Vec<PayoutTransfer>, ContractError> {
+        let token_client = TokenClient::new(e, &escrow.trustline.address);
+        if token_client.balance(&e.current_contract_address()) < amount {
+            return Err(ContractError::EscrowBalanceNotEnoughToSendEarnings);
+        }
+
+        let fee_result = Self::standard_fees(e, escrow, amount)?;
+        let mut plan = Self::fee_transfers(e, escrow, &fee_result)?;
+        Self::push(
+            &mut plan,
+            EscrowManager::get_receiver(escrow),
+            fee_result.receiver_amount,
+            PayoutReason::Release,
+        );
+        Ok(plan)
+    }
+
+    /// Takes the standard fees out of `total` and splits the remainder across
+    /// `distributions`, each recipient bearing a pro rata share of the fees.
+    pub fn plan_distributions(
+        e: &Env,
+        escrow: &Escrow,
+        distributions: &Map<Address, i128>,
+        total: i128,
+    ) -> Result<Vec<PayoutTransfer>, ContractError> {
+        let fee_result = Self::standard_fees(e, escrow, total)?;
+        let total_fees =
+            BasicMath::safe_add(fee_result.trustless_work_fee, fee_result.platform_fee)?;
+        let mut plan = Self::fee_transfers(e, escrow, &fee_result)?;
+
+        let fee_shares = Self::allocate_fee_shares(e, distributions, total_fees, total)?;
+        for (addr, amount) in distributions.iter() {
+            let fee_share = fee_shares.get(addr.clone()).unwrap_or(0);
+            let net_amount = BasicMath::safe_sub(amount, fee_share)?;
+            Self::push(&mut plan, addr, net_amount, PayoutReason::Distribution);
+        }
+
+        Ok(plan)
+    }
+
+    /// Fees and pro rata funder refunds for sending `amount` back to the
+    /// funders. Also returns the amount refunded after fees.
+    pub fn plan_refund(
+        e: &Env,
+        escrow: &Escrow,
+        amount: i128,
+    ) -> Result<(Vec<PayoutTransfer>, i128), ContractError> {
+        let token_client = TokenClient::new(e, &escrow.trustline.address);
+        if token_client.balance(&e.current_contract_address()) < amount {
+            return Err(ContractError::EscrowBalanceNotEnoughToSendEarnings);
+        }
+
+        let fee_result = Self::standard_fees(e, escrow, amount)?;
+        let mut plan = Self::fee_transfers(e, escrow, &fee_result)?;
+        let shares = FundingManager::refund_shares(e, escrow, fee_result.receiver_amount)?;
+        for (funder, share) in shares.iter() {
+            Self::push(&mut plan, funder, share, PayoutReason::Refund);
+        }
+        Ok((plan, fee_result.receiver_amount))
+    }
+
+    /// Fee-free refund of `amount` to the funders when an escrow is cancelled
+    /// or an executed ruling leaves a surplus behind.
+    pub fn plan_cancellation(
+        e: &Env,
+        escrow: &Escrow,
+        amount: i128,
+    ) -> Result<Vec<PayoutTransfer>, ContractError> {
+        let mut plan = Vec::new(e);
+        for (funder, share) in FundingManager::refund_shares(e, escrow, amount)?.iter() {
+            Self::push(&mut plan, funder, share, PayoutReason::Refund);
+        }
+        Ok(plan)
+    }
+
+    pub fn execute(e: &Env, escrow: &Escrow, plan: &Vec<PayoutTransfer>) {
+        let contract_address = e.current_contract_address();
+        let token_client = TokenClient::new(e, &escrow.trustline.address);
+        for transfer in plan.iter() {
+            token_client.transfer(&contract_address, &transfer.recipient, &transfer.amount);
+        }
+    }
+
+    /// Executes a plan that carried the standard fees on `amount` and adds
+    /// both to the running totals the next payout's fees are measured from.
+    pub fn execute_with_fees(
+        e: &Env,
+        escrow: &Escrow,
+        plan: &Vec<PayoutTransfer>,
+        amount: i128,
+    ) -> Result<(), ContractError> {
+        let mut totals = Self::get_fee_totals(e);
+        totals.volume = BasicMath::safe_add(totals.volume, amount)?;
+        for transfer in plan.iter() {
+            if matches!(
+                transfer.reason,
+                PayoutReason::PlatformFee | PayoutReason::RevenueShare
+            ) {
+                totals.platform_fee = BasicMath::safe_add(totals.platform_fee, transfer.amount)?;
+            }
+        }
+        e.storage().instance().set(&DataKey::FeeTotals, &totals);
+
+        Self::execute(e, escrow, plan);
+        Ok(())
+    }
+
+    pub fn get_fee_totals(e: &Env) -> FeeTotals {
+        e.storage()
+            .instance()
+            .get(&DataKey::FeeTotals)
+            .unwrap_or_default()
+    }
+
+    fn standard_fees(
+        e: &Env,
+        escrow: &Escrow,
+        amount: i128,
+    ) -> Result<StandardFeeResult, ContractError> {
+        FeeCalculator::calculate_standard_fees(
+            amount,
+            escrow.trustless_work_fee,
+            escrow.platform_fee,
+            &escrow.platform_fee_schedule,
+            &Self::get_fee_totals(e),
+        )
+    }
+
+    fn fee_transfers(
+        e: &Env,
+        escrow: &Escrow,
+        fee_result: &StandardFeeResult,
+    ) -> Result<Vec<PayoutTransfer>, ContractError> {
+        let mut plan = Vec::new(e);
+        Self::push(
+            &mut plan,
+            AdminManager::get_protocol_config(e).trustless_work_address,
+            fee_result.trustless_work_fee,
+            PayoutReason::TrustlessWorkFee,
+        );
+
+        let mut platform_amount = fee_result.platform_fee;
+        for share in escrow.revenue_shares.iter() {
+            let amount = Bps::new(share.bps)?.apply(fee_result.platform_fee, Rounding::Floor)?;
+            platform_amount = BasicMath::safe_sub(platform_amount, amount)?;
+            Self::push(
+                &mut plan,
+                share.recipient,
+                amount,
+                PayoutReason::RevenueShare,
+            );
+        }
+        Self::push(
+            &mut plan,
+            escrow.roles.platform_address.clone(),
+            platform_amount,
+            PayoutReason::PlatformFee,
+        );
+        Ok(plan)
+    }
+
+    fn push(
+        plan: &mut Vec<PayoutTransfer>,
+        recipient: Address,
+        amount: i128,
+        reason: PayoutReason,
+    ) {
+        if amount > 0 {
+            plan.push_back(PayoutTransfer {
+                recipient,
+                amount,
+                reason,
+            });
+        }
+    }
+
+    /// Splits `total_fees` across recipients in proportion to their amounts
+    /// using largest remainders, so the shares always add up to `total_fees`.
+    /// Ties go to the recipient that comes first in the map.
+    fn allocate_fee_shares(
+        e: &Env,
+        distributions: &Map<Address, i128>,
+        total_fees: i128,
+        total: i128,
+    ) -> Result<Map<Address, i128>, ContractError> {
+        let mut shares: Map<Address, i128> = Map::new(e);
+        let mut remainders: Map<Address, i128> = Map::new(e);
+        let mut allocated: i128 = 0;
+        for (addr, amount) in distributions.iter() {
+            let (share, remainder) = WideMath::mul_div_rem(amount, total_fees, total)?;
+            shares.set(addr.clone(), share);
+            remainders.set(addr, remainder);
+            allocated = BasicMath::safe_add(allocated, share)?;
+        }
+
+        let mut leftover = BasicMath::safe_sub(total_fees, allocated)?;
+        while leftover > 0 {
+            let mut largest: Option<(Address, i128)> = None;
+            for (addr, remainder) in remainders.iter() {
+                if largest.as_ref().is_none_or(|(_, best)| remainder > *best) {
+                    largest = Some((addr, remainder));
+                }
+            }
+            let (addr, _) = largest.ok_or(ContractError::DivisionError)?;
+            remainders.remove(addr.clone());
+            shares.set(addr.clone(), shares.get(addr).unwrap_or(0) + 1);
+            leftover -= 1;
+        }
+
+        Ok(shares)
+    }
+}
@@ -1,10 +1,41 @@
-use soroban_sdk::Address;
+use soroban_sdk::{Address, Map};
 
 use crate::{
     error::ContractError,
-    storage::types::{Escrow, Roles},
+    modules::fee::BASIS_POINTS_DENOMINATOR,
+    storage::types::{DisputeInfo, Escrow, EscrowStatus, Milestone, Roles, Ruling},
 };
 
+const MAX_PANEL_SIZE: u32 = 10;
+
+/// Escrows without a panel keep the single `dispute_resolver`; otherwise
+/// `panel_threshold` of the panel members must agree on a ruling.
+#[inline]
+pub fn validate_arbitration_panel(escrow: &Escrow) -> Result<(), ContractError> {
+    let panel = &escrow.arbitration_panel;
+    if panel.is_empty() {
+        if escrow.panel_threshold != 0 {
+            return Err(ContractError::InvalidEscrowConfiguration);
+        }
+        return Ok(());
+    }
+
+    if panel.len() > MAX_PANEL_SIZE
+        || escrow.panel_threshold == 0
+        || escrow.panel_threshold > panel.len()
+    {
+        return Err(ContractError::InvalidEscrowConfiguration);
+    }
+
+    for (index, member) in panel.iter().enumerate() {
+        if panel.first_index_of(&member) != Some(index as u32) {
+            return Err(ContractError::InvalidEscrowConfiguration);
+        }
+    }
+
+    Ok(())
+}
+
 #[inline]
 pub fn validate_dispute_resolution_conditions(
     escrow: &Escrow,
@@ -12,12 +43,57 @@ pub fn validate_dispute_resolution_conditions(
     current_balance: i128,
     total: i128,
 ) -> Result<(), ContractError> {
-    if dispute_resolver != &escrow.roles.dispute_resolver {
+    let is_resolver = if escrow.arbitration_panel.is_empty() {
+        dispute_resolver == &escrow.roles.dispute_resolver
+    } else {
+        escrow.arbitration_panel.contains(dispute_resolver)
+    };
+    if !is_resolver {
         return Err(ContractError::OnlyDisputeResolverCanExecuteThisFunction);
     }
 
-    if !escrow.flags.disputed {
-        return Err(ContractError::EscrowNotInDispute);
+    if current_balance < total {
+        return Err(ContractError::InsufficientFundsForResolution);
+    }
+
+    if total != current_balance {
+        return Err(ContractError::DistributionsMustEqualEscrowBalance);
+    }
+
+    if total <= 0 {
+        return Err(ContractError::TotalAmountCannotBeZero);
+    }
+
+    Ok(())
+}
+
+/// Shares are basis points of the balance at execution and must cover all of it.
+#[inline]
+pub fn validate_dispute_shares(shares: &Map<Address, u32>) -> Result<(), ContractError> {
+    let mut total: i128 = 0;
+    for (_addr, bps) in shares.iter() {
+        if bps == 0 {
+            return Err(ContractError::AmountsToBeTransferredShouldBePositive);
+        }
+        total += i128::from(bps);
+    }
+
+    if total != BASIS_POINTS_DENOMINATOR {
+        return Err(ContractError::DistributionsMustEqualEscrowBalance);
+    }
+
+    Ok(())
+}
+
+#[inline]
+pub fn validate_appeal_resolution_conditions(
+    escrow: &Escrow,
+    dispute_resolver: &Address,
+    current_balance: i128,
+    total: i128,
+) -> Result<(), ContractError> {
+    if escrow.appeal_resolver.as_ref() != Some(dispute_resolver) {
+        return Err(ContractError::OnlyDisputeResolverCanExecuteThisFunction);
     }
 
     if current_balance < total {
@@ -36,14 +112,67 @@ pub fn validate_dispute_resolution_conditions(
 }
 
 #[inline]
-pub fn validate_dispute_flag_change_conditions(
+pub fn validate_appeal_conditions(
     escrow: &Escrow,
+    ruling: &Ruling,
     signer: &Address,
+    now: u64,
+) -> Result<(), ContractError> {
+    if signer != &escrow.roles.approver && signer != &escrow.roles.service_provider {
+        return Err(ContractError::UnauthorizedToChangeDisputeFlag);
+    }
+
+    if ruling.appealed_at.is_some() {
+        return Err(ContractError::AppealNotAllowed);
+    }
+
+    let appeal_window = escrow
+        .appeal_window
+        .ok_or(ContractError::AppealNotAllowed)?;
+    let deadline = ruling
+        .ruled_at
+        .checked_add(appeal_window)
+        .ok_or(ContractError::Overflow)?;
+    if now > deadline {
+        return Err(ContractError::AppealNotAllowed);
+    }
+
+    Ok(())
+}
+
+#[inline]
+pub fn validate_ruling_execution_conditions(
+    escrow: &Escrow,
+    ruling: &Ruling,
+    now: u64,
+    current_balance: i128,
+    total: i128,
 ) -> Result<(), ContractError> {
-    if escrow.flags.disputed {
-        return Err(ContractError::EscrowAlreadyInDispute);
+    if ruling.appealed_at.is_some() {
+        return Err(ContractError::RulingNotExecutable);
+    }
+
+    let appeal_window = escrow.appeal_window.unwrap_or(0);
+    let deadline = ruling
+        .ruled_at
+        .checked_add(appeal_window)
+        .ok_or(ContractError::Overflow)?;
+    if now <= deadline {
+        return Err(ContractError::DeadlineNotReached);
+    }
+
+    if current_balance < total {
+        return Err(ContractError::InsufficientFundsForResolution);
     }
 
+    Ok(())
+}
+
+#[inline]
+pub fn validate_dispute_flag_change_conditions(
+    escrow: &Escrow,
+    signer: &Address,
+) -> Result<(), ContractError> {
     let Roles {
         approver,
         service_provider,
@@ -70,3 +199,144 @@ pub fn validate_dispute_flag_change_conditions(
 
     Ok(())
 }
+
+#[inline]
+pub fn validate_dispute_statement_conditions(
+    escrow: &Escrow,
+    signer: &Address,
+) -> Result<(), ContractError> {
+    if escrow.status != EscrowStatus::Disputed {
+        return Err(ContractError::EscrowNotInDispute);
+    }
+
+    validate_dispute_flag_change_conditions(escrow, signer)
+}
+
+/// The opener can withdraw alone; otherwise the approver and the service
+/// provider have to agree. Once a ruling or panel proposal is on record the
+/// dispute has to run its course.
+#[inline]
+pub fn validate_dispute_withdrawal_conditions(
+    escrow: &Escrow,
+    dispute: &DisputeInfo,
+    ruling: Option<&Ruling>,
+    proposals: &Map<Address, Map<Address, i128>>,
+    signer: &Address,
+) -> Result<(), ContractError> {
+    if ruling.is_some() || !proposals.is_empty() {
+        return Err(ContractError::EscrowOpenedForDisputeResolution);
+    }
+
+    if signer != &dispute.opened_by
+        && signer != &escrow.roles.approver
+        && signer != &escrow.roles.service_provider
+    {
+        return Err(ContractError::UnauthorizedToChangeDisputeFlag);
+    }
+
+    Ok(())
+}
+
+#[inline]
+pub fn validate_stale_dispute_conditions(
+    escrow: &Escrow,
+    dispute: &DisputeInfo,
+    ruling: Option<&Ruling>,
+    now: u64,
+) -> Result<(), ContractError> {
+    // A posted ruling is settled through `execute_ruling`. Once appealed, the
+    // appeal resolver gets the resolution window (or, without one, the appeal
+    // window again) before the fallback applies.
+    let (started_at, window) = match ruling {
+        Some(ruling) => {
+            let appealed_at = ruling
+                .appealed_at
+                .ok_or(ContractError::EscrowOpenedForDisputeResolution)?;
+            let window = escrow
+                .dispute_resolution_window
+                .or(escrow.appeal_window)
+                .ok_or(ContractError::DeadlineNotReached)?;
+            (appealed_at, window)
+        }
+        None => {
+            let window = escrow
+                .dispute_resolution_window
+                .ok_or(ContractError::DeadlineNotReached)?;
+            (dispute.opened_at, window)
+        }
+    };
+
+    let deadline = started_at
+        .checked_add(window)
+        .ok_or(ContractError::Overflow)?;
+    if now <= deadline {
+        return Err(ContractError::DeadlineNotReached);
+    }
+
+    Ok(())
+}
+
+#[inline]
+pub fn validate_milestone_dispute_conditions(
+    escrow: &Escrow,
+    milestone: &Milestone,
+    signer: &Address,
+) -> Result<(), ContractError> {
+    validate_dispute_flag_change_conditions(escrow, signer)?;
+
+    // Milestone rulings are paid out at once, so escrows that promise an
+    // appeal before payout have to dispute the escrow as a whole.
+    if escrow.appeal_window.is_some() {
+        return Err(ContractError::InvalidEscrowConfiguration);
+    }
+
+    if milestone.released || milestone.expired {
+        return Err(ContractError::MilestoneAlreadyReleased);
+    }
+
+    if milestone.disputed {
+        return Err(ContractError::MilestoneInDispute);
+    }
+
+    Ok(())
+}
+
+#[inline]
+pub fn validate_milestone_dispute_resolution_conditions(
+    escrow: &Escrow,
+    milestone: &Milestone,
+    dispute_resolver: &Address,
+    current_balance: i128,
+    total: i128,
+) -> Result<(), ContractError> {
+    let is_resolver = if escrow.arbitration_panel.is_empty() {
+        dispute_resolver == &escrow.roles.dispute_resolver
+    } else {
+        escrow.arbitration_panel.contains(dispute_resolver)
+    };
+    if !is_resolver {
+        return Err(ContractError::OnlyDisputeResolverCanExecuteThisFunction);
+    }
+
+    if escrow.appeal_window.is_some() {
+        return Err(ContractError::InvalidEscrowConfiguration);
+    }
+
+    if !milestone.disputed {
+        return Err(ContractError::EscrowNotInDispute);
+    }
+
+    if total <= 0 {
+        return Err(ContractError::TotalAmountCannotBeZero);
+    }
+
+    if current_balance < total {
+        return Err(ContractError::InsufficientFundsForResolution);
+    }
+
+    if total != milestone.amount {
+        return Err(ContractError::DistributionsMustEqualEscrowBalance);
+    }
+
+    Ok(())
+}
@@ -1,37 +1,168 @@
 use soroban_sdk::{Address, Env};
 
 use crate::{
+    core::admin::AdminManager,
     error::ContractError,
-    storage::types::{DataKey, Escrow},
+    modules::{
+        fee::{BASIS_POINTS_DENOMINATOR, TRUSTLESS_WORK_FEE_ROUNDING},
+        math::{BasicArithmetic, BasicMath, Bps},
+    },
+    storage::types::{DataKey, Escrow, EscrowStatus, Milestone, MilestoneStatus},
 };
 
+use super::dispute::validate_arbitration_panel;
+
+const MAX_REVENUE_SHARES: u32 = 10;
+/// `release_funds` records a full release in the milestone header, so the
+/// paths that cover every milestone only have to read the entries.
+const MAX_MILESTONES: u32 = 200;
+
 #[inline]
 pub fn validate_release_conditions(
     escrow: &Escrow,
     release_signer: &Address,
 ) -> Result<(), ContractError> {
-    if escrow.flags.released {
-        return Err(ContractError::EscrowAlreadyReleased);
+    if release_signer != &escrow.roles.release_signer {
+        return Err(ContractError::OnlyReleaseSignerCanReleaseEarnings);
+    }
+
+    if escrow.milestones.is_empty() {
+        return Err(ContractError::NoMilestoneDefined);
     }
 
-    if escrow.flags.resolved {
-        return Err(ContractError::EscrowAlreadyResolved);
+    if escrow.milestones.iter().any(|milestone| milestone.disputed) {
+        return Err(ContractError::MilestoneInDispute);
     }
 
+    if !escrow.milestones.iter().all(|milestone| {
+        milestone.status == MilestoneStatus::Approved || milestone.expired || milestone.released
+    }) {
+        return Err(ContractError::EscrowNotCompleted);
+    }
+
+    Ok(())
+}
+
+#[inline]
+pub fn validate_milestone_release_conditions(
+    escrow: &Escrow,
+    milestone: &Milestone,
+    release_signer: &Address,
+) -> Result<(), ContractError> {
     if release_signer != &escrow.roles.release_signer {
         return Err(ContractError::OnlyReleaseSignerCanReleaseEarnings);
     }
 
-    if escrow.milestones.is_empty() {
-        return Err(ContractError::NoMilestoneDefined);
+    if milestone.disputed {
+        return Err(ContractError::MilestoneInDispute);
     }
 
-    if !escrow.milestones.iter().all(|milestone| milestone.approved) {
-        return Err(ContractError::EscrowNotCompleted);
+    if milestone.status != MilestoneStatus::Approved {
+        return Err(ContractError::InvalidStatusTransition);
+    }
+
+    if milestone.released {
+        return Err(ContractError::MilestoneAlreadyReleased);
+    }
+
+    Ok(())
+}
+
+#[inline]
+pub fn validate_cancel_escrow_conditions(
+    escrow: &Escrow,
+    signer: &Address,
+) -> Result<(), ContractError> {
+    if escrow.milestones.iter().any(|m| m.disputed) {
+        return Err(ContractError::MilestoneInDispute);
+    }
+
+    if signer == &escrow.roles.platform_address {
+        if escrow
+            .milestones
+            .iter()
+            .any(|m| m.status == MilestoneStatus::Approved)
+        {
+            return Err(ContractError::UnauthorizedToCancelEscrow);
+        }
+        return Ok(());
+    }
+
+    if signer != &escrow.roles.approver && signer != &escrow.roles.service_provider {
+        return Err(ContractError::UnauthorizedToCancelEscrow);
+    }
+
+    Ok(())
+}
+
+/// Tier rates leave room for the protocol fee and every milestone payout can
+/// cover the floor, so the schedule never takes more than the amount paid out.
+#[inline]
+pub fn validate_fee_schedule(escrow: &Escrow) -> Result<(), ContractError> {
+    let schedule = &escrow.platform_fee_schedule;
+    let max_tier_bps = 10_000u32.saturating_sub(escrow.trustless_work_fee);
+
+    if !schedule.tiers.is_empty() && escrow.platform_fee != 0 {
+        return Err(ContractError::InvalidEscrowConfiguration);
+    }
+
+    let mut previous_from: Option<i128> = None;
+    for tier in schedule.tiers.iter() {
+        if tier.fee_bps > max_tier_bps {
+            return Err(ContractError::PlatformFeeTooHigh);
+        }
+        let ordered = match previous_from {
+            None => tier.from_amount == 0,
+            Some(previous) => tier.from_amount > previous,
+        };
+        if !ordered {
+            return Err(ContractError::InvalidEscrowConfiguration);
+        }
+        previous_from = Some(tier.from_amount);
+    }
+
+    if schedule.min_fee < 0 || schedule.max_fee.is_some_and(|max| max < schedule.min_fee) {
+        return Err(ContractError::InvalidEscrowConfiguration);
+    }
+
+    for milestone in escrow.milestones.iter() {
+        if milestone.amount <= 0 {
+            continue;
+        }
+        let trustless_work_fee = Bps::new(escrow.trustless_work_fee)?
+            .apply(milestone.amount, TRUSTLESS_WORK_FEE_ROUNDING)?;
+        let payable = BasicMath::safe_sub(milestone.amount, trustless_work_fee)?;
+        if schedule.min_fee > payable {
+            return Err(ContractError::PlatformFeeTooHigh);
+        }
+    }
+
+    Ok(())
+}
+
+/// Shares are cut from the platform fee, so together they may take all of it
+/// but never more.
+#[inline]
+pub fn validate_revenue_shares(escrow: &Escrow) -> Result<(), ContractError> {
+    if escrow.revenue_shares.len() > MAX_REVENUE_SHARES {
+        return Err(ContractError::InvalidEscrowConfiguration);
+    }
+
+    let mut total_bps: u32 = 0;
+    for (index, share) in escrow.revenue_shares.iter().enumerate() {
+        let duplicate = escrow
+            .revenue_shares
+            .iter()
+            .take(index)
+            .any(|other| other.recipient == share.recipient);
+        if share.bps == 0 || duplicate {
+            return Err(ContractError::InvalidEscrowConfiguration);
+        }
+        total_bps = total_bps.saturating_add(share.bps);
     }
 
-    if escrow.flags.disputed {
-        return Err(ContractError::EscrowOpenedForDisputeResolution);
+    if i128::from(total_bps) > BASIS_POINTS_DENOMINATOR {
+        return Err(ContractError::PlatformFeeTooHigh);
     }
 
     Ok(())
@@ -50,10 +181,21 @@ pub fn validate_escrow_conditions(
         return Err(ContractError::PlatformFeeTooHigh);
     }
 
-    const TRUSTLESS_WORK_FEE_BPS: u32 = 30;
-    if (new_escrow.platform_fee as u32) + TRUSTLESS_WORK_FEE_BPS > 10_000 {
+    if new_escrow.platform_fee + new_escrow.trustless_work_fee > 10_000 {
         return Err(ContractError::PlatformFeeTooHigh);
     }
+    validate_fee_schedule(new_escrow)?;
+    validate_revenue_shares(new_escrow)?;
+
+    if new_escrow.dispute_resolution_window == Some(0) {
+        return Err(ContractError::InvalidEscrowConfiguration);
+    }
+    validate_arbitration_panel(new_escrow)?;
+    if new_escrow.appeal_window == Some(0)
+        || new_escrow.appeal_window.is_some() != new_escrow.appeal_resolver.is_some()
+    {
+        return Err(ContractError::InvalidEscrowConfiguration);
+    }
 
     if new_escrow.amount < 0 {
         return Err(ContractError::AmountCannotBeZero);
@@ -62,22 +204,38 @@ pub fn validate_escrow_conditions(
     if new_escrow.milestones.is_empty() {
         return Err(ContractError::NoMilestoneDefined);
     }
-    if new_escrow.milestones.len() > 50 {
+    if new_escrow.milestones.len() > MAX_MILESTONES {
         return Err(ContractError::TooManyMilestones);
     }
 
+    let mut milestones_total: i128 = 0;
+    for milestone in new_escrow.milestones.iter() {
+        if milestone.amount < 0 {
+            return Err(ContractError::AmountCannotBeZero);
+        }
+        milestones_total = BasicMath::safe_add(milestones_total, milestone.amount)?;
+    }
+    if milestones_total != new_escrow.amount {
+        return Err(ContractError::InvalidEscrowConfiguration);
+    }
+
     if is_init {
-        if new_escrow.flags.released
-            || new_escrow.flags.disputed
-            || new_escrow.flags.resolved
-            || new_escrow.milestones.iter().any(|m| m.approved)
+        if new_escrow.status != EscrowStatus::Draft
+            || new_escrow.milestones.iter().any(|m| {
+                m.status != MilestoneStatus::Pending
+                    || m.released
+                    || m.expired
+                    || m.revisions > 0
+                    || m.disputed
+            })
         {
             return Err(ContractError::FlagsMustBeFalse);
         }
     } else {
         let existing = existing_escrow.ok_or(ContractError::EscrowNotFound)?;
 
-        let caller = platform_address.ok_or(ContractError::OnlyPlatformAddressExecuteThisFunction)?;
+        let caller =
+            platform_address.ok_or(ContractError::OnlyPlatformAddressExecuteThisFunction)?;
         if caller != &existing.roles.platform_address {
             return Err(ContractError::OnlyPlatformAddressExecuteThisFunction);
         }
@@ -86,15 +244,12 @@ pub fn validate_escrow_conditions(
             return Err(ContractError::PlatformAddressCannotBeChanged);
         }
 
-        if existing.flags.disputed {
-            return Err(ContractError::EscrowOpenedForDisputeResolution);
+        if existing.trustless_work_fee != new_escrow.trustless_work_fee {
+            return Err(ContractError::EscrowPropertiesMismatch);
         }
 
-        if new_escrow.flags.released
-            || new_escrow.flags.disputed
-            || new_escrow.flags.resolved
-        {
-            return Err(ContractError::FlagsMustBeFalse);
+        if existing.status != new_escrow.status {
+            return Err(ContractError::EscrowPropertiesMismatch);
         }
 
         let has_funds = contract_balance.unwrap_or(0) > 0;
@@ -103,11 +258,18 @@ pub fn validate_escrow_conditions(
                 || existing.title != new_escrow.title
                 || existing.description != new_escrow.description
                 || existing.roles != new_escrow.roles
-                || existing.amount != new_escrow.amount
                 || existing.platform_fee != new_escrow.platform_fee
-                || existing.flags != new_escrow.flags
+                || existing.platform_fee_schedule != new_escrow.platform_fee_schedule
+                || existing.revenue_shares != new_escrow.revenue_shares
                 || existing.trustline != new_escrow.trustline
                 || existing.receiver_memo != new_escrow.receiver_memo
+                || existing.max_revisions != new_escrow.max_revisions
+                || existing.dispute_resolution_window != new_escrow.dispute_resolution_window
+                || existing.dispute_fallback != new_escrow.dispute_fallback
+                || existing.arbitration_panel != new_escrow.arbitration_panel
+                || existing.panel_threshold != new_escrow.panel_threshold
+                || existing.appeal_window != new_escrow.appeal_window
+                || existing.appeal_resolver != new_escrow.appeal_resolver
             {
                 return Err(ContractError::EscrowPropertiesMismatch);
             }
@@ -123,17 +285,31 @@ pub fn validate_escrow_conditions(
                 }
             }
 
+            // Appended milestones grow `amount` by their own amounts, which the
+            // milestone sum check above already enforces.
             for i in old_len..new_len {
-                if new_escrow.milestones.get(i).unwrap().approved {
+                let milestone = new_escrow.milestones.get(i).unwrap();
+                if milestone.status != MilestoneStatus::Pending
+                    || milestone.released
+                    || milestone.expired
+                    || milestone.revisions > 0
+                    || milestone.disputed
+                {
                     return Err(ContractError::FlagsMustBeFalse);
                 }
             }
         } else {
-            if existing.milestones.iter().any(|m| m.approved) {
+            if existing
+                .milestones
+                .iter()
+                .any(|m| m.status == MilestoneStatus::Approved)
+            {
                 return Err(ContractError::MilestoneApprovedCantChangeEscrowProperties);
             }
 
-            if new_escrow.milestones.iter().any(|m| m.approved) {
+            if new_escrow.milestones.iter().any(|m| {
+                m.status == MilestoneStatus::Approved || m.released || m.expired || m.disputed
+            }) {
                 return Err(ContractError::FlagsMustBeFalse);
             }
         }
@@ -166,6 +342,13 @@ pub fn validate_initialize_escrow_conditions(
     if e.storage().instance().has(&DataKey::Escrow) {
         return Err(ContractError::EscrowAlreadyInitialized);
     }
+    // The escrow carries the protocol rate in force today, so later admin
+    // changes never touch terms the parties already agreed to.
+    if escrow_properties.trustless_work_fee
+        != AdminManager::get_protocol_config(e).trustless_work_fee
+    {
+        return Err(ContractError::EscrowPropertiesMismatch);
+    }
     validate_escrow_conditions(None, &escrow_properties, None, None, true)
 }
 
@@ -179,7 +362,11 @@ pub fn validate_fund_escrow_conditions(
         return Err(ContractError::AmountCannotBeZero);
     }
 
-    if !stored_escrow.eq(&expected_escrow) {
+    // The status moves as other funders top up, so it is not part of the
+    // terms the signer agrees to.
+    let mut expected_terms = expected_escrow.clone();
+    expected_terms.status = stored_escrow.status;
+    if !stored_escrow.eq(&expected_terms) {
         return Err(ContractError::EscrowPropertiesMismatch);
     }
 
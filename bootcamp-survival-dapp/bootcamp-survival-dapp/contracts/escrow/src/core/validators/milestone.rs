@@ -2,21 +2,37 @@ use soroban_sdk::Address;
 
 use crate::{
     error::ContractError,
-    storage::types::{Escrow, Milestone},
+    storage::types::{Escrow, Milestone, MilestoneStatus},
 };
 
+use super::status::validate_milestone_status_transition;
+
 #[inline]
 pub fn validate_milestone_status_change_conditions(
     escrow: &Escrow,
+    milestone: &Milestone,
+    new_status: MilestoneStatus,
     service_provider: &Address,
 ) -> Result<(), ContractError> {
     if service_provider != &escrow.roles.service_provider {
         return Err(ContractError::OnlyServiceProviderChangeMilstoneStatus);
     }
 
-    if escrow.milestones.is_empty() {
-        return Err(ContractError::NoMilestoneDefined);
+    if milestone.released || milestone.expired {
+        return Err(ContractError::MilestoneAlreadyReleased);
+    }
+
+    if milestone.disputed {
+        return Err(ContractError::MilestoneInDispute);
+    }
+
+    if matches!(
+        new_status,
+        MilestoneStatus::Approved | MilestoneStatus::ChangesRequested | MilestoneStatus::Rejected
+    ) {
+        return Err(ContractError::OnlyApproverChangeMilstoneFlag);
     }
+    validate_milestone_status_transition(milestone.status, new_status)?;
 
     Ok(())
 }
@@ -31,16 +47,65 @@ pub fn validate_milestone_flag_change_conditions(
         return Err(ContractError::OnlyApproverChangeMilstoneFlag);
     }
 
-    if milestone.approved {
-        return Err(ContractError::MilestoneHasAlreadyBeenApproved);
+    if milestone.released || milestone.expired {
+        return Err(ContractError::MilestoneAlreadyReleased);
+    }
+
+    if milestone.disputed {
+        return Err(ContractError::MilestoneInDispute);
     }
 
-    if milestone.status.is_empty() {
-        return Err(ContractError::EmptyMilestoneStatus);
+    validate_milestone_status_transition(milestone.status, MilestoneStatus::Approved)?;
+
+    Ok(())
+}
+
+#[inline]
+pub fn validate_milestone_changes_request_conditions(
+    escrow: &Escrow,
+    milestone: &Milestone,
+    approver: &Address,
+) -> Result<(), ContractError> {
+    if approver != &escrow.roles.approver {
+        return Err(ContractError::OnlyApproverChangeMilstoneFlag);
+    }
+
+    if milestone.released || milestone.expired {
+        return Err(ContractError::MilestoneAlreadyReleased);
+    }
+
+    if milestone.disputed {
+        return Err(ContractError::MilestoneInDispute);
+    }
+
+    validate_milestone_status_transition(milestone.status, MilestoneStatus::ChangesRequested)?;
+
+    Ok(())
+}
+
+#[inline]
+pub fn validate_milestone_expiry_conditions(
+    milestone: &Milestone,
+    now: u64,
+) -> Result<(), ContractError> {
+    // A milestone settled through its own dispute keeps its status but is paid
+    if milestone.released || milestone.expired {
+        return Err(ContractError::MilestoneAlreadyReleased);
+    }
+
+    if milestone.disputed {
+        return Err(ContractError::MilestoneInDispute);
+    }
+
+    if milestone.status == MilestoneStatus::Approved {
+        return Err(ContractError::MilestoneHasAlreadyBeenApproved);
     }
 
-    if escrow.milestones.is_empty() {
-        return Err(ContractError::NoMilestoneDefined);
+    let due_ledger_timestamp = milestone
+        .due_ledger_timestamp
+        .ok_or(ContractError::DeadlineNotReached)?;
+    if now <= due_ledger_timestamp {
+        return Err(ContractError::DeadlineNotReached);
     }
 
     Ok(())
@@ -0,0 +1,75 @@
+use crate::{
+    error::ContractError,
+    storage::types::{EscrowStatus, MilestoneStatus},
+};
+
+/// Every operation that can move an escrow through its lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EscrowAction {
+    Fund,
+    UpdateProperties,
+    UpdateMilestone,
+    SettleMilestone,
+    Release,
+    Cancel,
+    Dispute,
+    Resolve,
+    WithdrawDispute,
+}
+
+/// Single transition table for the escrow lifecycle. Returns the status the
+/// escrow moves to when `action` is applied in `current`.
+#[inline]
+pub fn validate_status_transition(
+    current: EscrowStatus,
+    action: EscrowAction,
+) -> Result<EscrowStatus, ContractError> {
+    use EscrowAction::*;
+    use EscrowStatus::*;
+
+    match (current, action) {
+        (Draft, Fund) => Ok(Funded),
+        (Funded | InProgress, Fund) => Ok(current),
+
+        (Draft | Funded | InProgress, UpdateProperties) => Ok(current),
+
+        (Draft | Funded | InProgress, UpdateMilestone) => Ok(InProgress),
+
+        (Funded | InProgress, SettleMilestone) => Ok(current),
+        (Funded | InProgress, Release) => Ok(Released),
+
+        (Draft | Funded | InProgress, Cancel) => Ok(Cancelled),
+
+        (Draft | Funded | InProgress, Dispute) => Ok(Disputed),
+        (Disputed, Dispute) => Err(ContractError::EscrowAlreadyInDispute),
+
+        (Disputed, Resolve) => Ok(Resolved),
+        (Disputed, WithdrawDispute) => Ok(InProgress),
+
+        (Released, _) => Err(ContractError::EscrowAlreadyReleased),
+        (Resolved, _) => Err(ContractError::EscrowAlreadyResolved),
+        (Cancelled, _) => Err(ContractError::InvalidStatusTransition),
+        (Disputed, _) => Err(ContractError::EscrowOpenedForDisputeResolution),
+        (_, Resolve | WithdrawDispute) => Err(ContractError::EscrowNotInDispute),
+        _ => Err(ContractError::InvalidStatusTransition),
+    }
+}
+
+/// Milestone lifecycle. The service provider drives a milestone up to
+/// `Submitted`; only the approver can move it on from there.
+#[inline]
+pub fn validate_milestone_status_transition(
+    current: MilestoneStatus,
+    next: MilestoneStatus,
+) -> Result<(), ContractError> {
+    use MilestoneStatus::*;
+
+    match (current, next) {
+        (Pending | ChangesRequested, InProgress) => Ok(()),
+        (Pending | InProgress | ChangesRequested, Submitted) => Ok(()),
+        (Submitted, Approved | ChangesRequested | Rejected) => Ok(()),
+
+        (Approved, _) => Err(ContractError::MilestoneHasAlreadyBeenApproved),
+        _ => Err(ContractError::InvalidStatusTransition),
+    }
+}
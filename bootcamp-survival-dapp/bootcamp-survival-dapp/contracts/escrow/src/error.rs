@@ -35,7 +35,6 @@ pub enum ContractError {
     AmountsToBeTransferredShouldBePositive = 38,
     DistributionsMustEqualEscrowBalance = 39,
     MilestoneHasAlreadyBeenApproved = 29,
-    EmptyMilestoneStatus = 30,
     PlatformFeeTooHigh = 31,
     FlagsMustBeFalse = 32,
     EscrowPropertiesMismatch = 33,
@@ -45,6 +44,16 @@ pub enum ContractError {
     PlatformAddressCannotBeChanged = 37,
     DisputeResolverCannotDisputeTheEscrow = 40,
     TotalAmountCannotBeZero = 41,
+    MilestoneAlreadyReleased = 44,
+    UnauthorizedToCancelEscrow = 46,
+    TooManyFunders = 48,
+    DeadlineNotReached = 49,
+    InvalidStatusTransition = 51,
+    MilestoneInDispute = 54,
+    InvalidEscrowConfiguration = 57,
+    AppealNotAllowed = 58,
+    RulingNotExecutable = 59,
+    ProtocolFeeTooHigh = 60,
 }
 
 impl fmt::Display for ContractError {
@@ -81,7 +90,7 @@ impl fmt::Display for ContractError {
                 write!(f, "Only the dispute resolver can execute this function")
             }
             ContractError::EscrowAlreadyInDispute => write!(f, "Escrow already in dispute"),
-            ContractError::EscrowNotInDispute => write!(f, "Escrow not in dispute"),
+            ContractError::EscrowNotInDispute => write!(f, "Escrow or milestone not in dispute"),
             ContractError::InsufficientFundsForResolution => {
                 write!(f, "Insufficient funds for resolution")
             }
@@ -112,7 +121,7 @@ impl fmt::Display for ContractError {
                 write!(f, "You are not authorized to change the dispute flag")
             }
             ContractError::TooManyMilestones => {
-                write!(f, "Cannot define more than 50 milestones in an escrow")
+                write!(f, "Cannot define more than 200 milestones in an escrow")
             }
             ContractError::ReceiverAndApproverFundsNotEqual => {
                 write!(
@@ -127,7 +136,7 @@ impl fmt::Display for ContractError {
                 )
             }
             ContractError::DistributionsMustEqualEscrowBalance => {
-                write!(f, "The sum of distributions must equal the current escrow balance when resolving an escrow dispute.")
+                write!(f, "The sum of distributions must equal the current escrow balance when resolving an escrow dispute, or the milestone amount when resolving a milestone dispute.")
             }
             ContractError::MilestoneHasAlreadyBeenApproved => {
                 write!(
@@ -135,14 +144,11 @@ impl fmt::Display for ContractError {
                     "You cannot approve a milestone that has already been approved previously"
                 )
             }
-            ContractError::EmptyMilestoneStatus => {
-                write!(f, "The milestone status cannot be empty")
-            }
             ContractError::PlatformFeeTooHigh => {
                 write!(f, "The platform fee cannot exceed 99%")
             }
             ContractError::FlagsMustBeFalse => {
-                write!(f, "All flags (approved, disputed, released) must be false in order to execute this function.")
+                write!(f, "The escrow must be in Draft status, new milestones must be Pending and no milestone may be released or expired in order to execute this function.")
             }
             ContractError::EscrowPropertiesMismatch => {
                 write!(
@@ -172,7 +178,61 @@ impl fmt::Display for ContractError {
                 write!(f, "The dispute resolver cannot dispute the escrow.")
             }
             ContractError::TotalAmountCannotBeZero => {
-                write!(f, "The total amount to be distributed cannot be equal to zero.")
+                write!(
+                    f,
+                    "The total amount to be distributed cannot be equal to zero."
+                )
+            }
+            ContractError::MilestoneAlreadyReleased => {
+                write!(
+                    f,
+                    "The funds of this milestone have already been released or refunded."
+                )
+            }
+            ContractError::UnauthorizedToCancelEscrow => {
+                write!(
+                    f,
+                    "Only the platform, before any milestone is approved, or the approver together with the service provider can cancel the escrow."
+                )
+            }
+            ContractError::TooManyFunders => {
+                write!(
+                    f,
+                    "The escrow cannot be funded by more than 20 addresses, each first contributing at least a twentieth of the amount"
+                )
+            }
+            ContractError::DeadlineNotReached => {
+                write!(
+                    f,
+                    "The milestone deadline or dispute resolution window is not set or has not passed yet"
+                )
+            }
+            ContractError::InvalidStatusTransition => {
+                write!(
+                    f,
+                    "This operation is not allowed in the current escrow or milestone status"
+                )
+            }
+            ContractError::MilestoneInDispute => {
+                write!(f, "The milestone is frozen while its dispute is open")
+            }
+            ContractError::InvalidEscrowConfiguration => {
+                write!(f, "The escrow configuration is invalid")
+            }
+            ContractError::AppealNotAllowed => {
+                write!(
+                    f,
+                    "There is no ruling open to appeal or its appeal window has closed"
+                )
+            }
+            ContractError::RulingNotExecutable => {
+                write!(
+                    f,
+                    "There is no ruling awaiting execution or it has been appealed"
+                )
+            }
+            ContractError::ProtocolFeeTooHigh => {
+                write!(f, "The Trustless Work fee exceeds the allowed maximum")
             }
         }
     }
@@ -1,63 +1,282 @@
-use crate::storage::types::Escrow;
-use soroban_sdk::{contractevent, String};
+use crate::storage::types::{
+    DisputeFallback, Escrow, EscrowStatus, MilestoneStatus, PayoutReason, PayoutTransfer, Ruling,
+};
+use soroban_sdk::{contractevent, Env, Map, String, Vec};
 
-#[contractevent(topics = ["tw_init"], data_format = "vec")]
+// Every event follows the versioned schema: the schema version and, for
+// escrow events, the engagement id are topics, and the data carries only what
+// changed. The whole escrow is published by `FullSnapshot` on init and update.
+
+#[contractevent(topics = ["tw_init", "v1"], data_format = "vec")]
 #[derive(Clone)]
 pub struct InitEsc {
+    #[topic]
+    pub engagement_id: String,
+    pub platform: soroban_sdk::Address,
+}
+
+#[contractevent(topics = ["tw_full_snapshot", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct FullSnapshot {
+    #[topic]
+    pub engagement_id: String,
     pub escrow: Escrow,
 }
 
-#[contractevent(topics = ["tw_fund"], data_format = "vec")]
+#[contractevent(topics = ["tw_fund", "v1"], data_format = "vec")]
 #[derive(Clone)]
 pub struct FundEsc {
+    #[topic]
+    pub engagement_id: String,
     pub signer: soroban_sdk::Address,
     pub amount: i128,
 }
 
-#[contractevent(topics = ["tw_release"], data_format = "single-value")]
+#[contractevent(topics = ["tw_release", "v1"], data_format = "single-value")]
 #[derive(Clone)]
 pub struct DisEsc {
+    #[topic]
+    pub engagement_id: String,
     pub release_signer: soroban_sdk::Address,
 }
 
-#[contractevent(topics = ["tw_update"], data_format = "vec")]
+#[contractevent(topics = ["tw_update", "v1"], data_format = "vec")]
 #[derive(Clone)]
 pub struct ChgEsc {
+    #[topic]
+    pub engagement_id: String,
     pub platform: soroban_sdk::Address,
+}
+
+#[contractevent(topics = ["tw_cancel", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct CancelEsc {
+    #[topic]
     pub engagement_id: String,
-    pub new_escrow_properties: Escrow,
+    pub signer: soroban_sdk::Address,
+    pub refunded_amount: i128,
 }
 
 // Milestones
-#[contractevent(topics = ["tw_ms_change"], data_format = "vec")]
+#[contractevent(topics = ["tw_ms_change", "v1"], data_format = "vec")]
 #[derive(Clone)]
 pub struct MilestoneStatusChanged {
-    pub escrow: Escrow,
+    #[topic]
+    pub engagement_id: String,
+    pub milestone_index: i128,
+    pub old_status: MilestoneStatus,
+    pub new_status: MilestoneStatus,
+    pub actor: soroban_sdk::Address,
 }
 
-#[contractevent(topics = ["tw_ms_approve"], data_format = "vec")]
+#[contractevent(topics = ["tw_ms_approve", "v1"], data_format = "vec")]
 #[derive(Clone)]
 pub struct MilestoneApproved {
-    pub escrow: Escrow,
+    #[topic]
+    pub engagement_id: String,
+    pub milestone_index: i128,
+    pub old_status: MilestoneStatus,
+    pub new_status: MilestoneStatus,
+    pub actor: soroban_sdk::Address,
+}
+
+#[contractevent(topics = ["tw_ms_release", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct MilestoneReleased {
+    #[topic]
+    pub engagement_id: String,
+    pub release_signer: soroban_sdk::Address,
+    pub milestone_index: i128,
+}
+
+#[contractevent(topics = ["tw_ms_expire", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct MilestoneExpired {
+    #[topic]
+    pub engagement_id: String,
+    pub milestone_index: i128,
+    pub refunded_amount: i128,
+}
+
+#[contractevent(topics = ["tw_ms_changes", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct MilestoneChangesRequested {
+    #[topic]
+    pub engagement_id: String,
+    pub milestone_index: i128,
+    pub revisions: u32,
+    pub reason: String,
 }
 
 // Disputes
-#[contractevent(topics = ["tw_disp_resolve"], data_format = "vec")]
+/// `resolver` is `None` when an appealable ruling is executed.
+#[contractevent(topics = ["tw_disp_resolve", "v1"], data_format = "vec")]
 #[derive(Clone)]
 pub struct DisputeResolved {
-    pub escrow: Escrow,
+    #[topic]
+    pub engagement_id: String,
+    pub resolver: Option<soroban_sdk::Address>,
+}
+
+#[contractevent(topics = ["tw_disp_proposal", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct DisputeProposalSubmitted {
+    #[topic]
+    pub engagement_id: String,
+    pub resolver: soroban_sdk::Address,
+    pub distributions: Map<soroban_sdk::Address, i128>,
+    pub approvals: u32,
+}
+
+#[contractevent(topics = ["tw_ms_disp_proposal", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct MilestoneDisputeProposed {
+    #[topic]
+    pub engagement_id: String,
+    pub milestone_index: i128,
+    pub resolver: soroban_sdk::Address,
+    pub distributions: Map<soroban_sdk::Address, i128>,
+    pub approvals: u32,
+}
+
+#[contractevent(topics = ["tw_disp_ruling", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct DisputeRuled {
+    #[topic]
+    pub engagement_id: String,
+    pub resolver: soroban_sdk::Address,
+    pub distributions: Map<soroban_sdk::Address, i128>,
+    pub ruled_at: u64,
 }
 
-#[contractevent(topics = ["tw_dispute"], data_format = "vec")]
+#[contractevent(topics = ["tw_disp_appeal", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct RulingAppealed {
+    #[topic]
+    pub engagement_id: String,
+    pub signer: soroban_sdk::Address,
+    pub ruling: Ruling,
+}
+
+#[contractevent(topics = ["tw_dispute_withdrawn", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct DisputeWithdrawn {
+    #[topic]
+    pub engagement_id: String,
+    pub signer: soroban_sdk::Address,
+    pub new_status: EscrowStatus,
+}
+
+#[contractevent(topics = ["tw_disp_finalize", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct StaleDisputeFinalized {
+    #[topic]
+    pub engagement_id: String,
+    pub fallback: DisputeFallback,
+}
+
+#[contractevent(topics = ["tw_dispute", "v1"], data_format = "vec")]
 #[derive(Clone)]
 pub struct EscrowDisputed {
-    pub escrow: Escrow,
+    #[topic]
+    pub engagement_id: String,
+    pub signer: soroban_sdk::Address,
+}
+
+#[contractevent(topics = ["tw_ms_dispute", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct MilestoneDisputed {
+    #[topic]
+    pub engagement_id: String,
+    pub milestone_index: i128,
+    pub signer: soroban_sdk::Address,
+}
+
+#[contractevent(topics = ["tw_ms_disp_resolve", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct MilestoneDisputeResolved {
+    #[topic]
+    pub engagement_id: String,
+    pub milestone_index: i128,
+    pub resolver: soroban_sdk::Address,
+    pub new_status: EscrowStatus,
+}
+
+#[contractevent(topics = ["tw_disp_statement", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct DisputeStatementSubmitted {
+    #[topic]
+    pub engagement_id: String,
+    pub signer: soroban_sdk::Address,
+    pub statement_index: u32,
+}
+
+#[contractevent(topics = ["tw_rev_share", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct RevenueSharePaid {
+    #[topic]
+    pub engagement_id: String,
+    pub recipient: soroban_sdk::Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["tw_payout", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct PayoutMade {
+    #[topic]
+    pub engagement_id: String,
+    pub recipient: soroban_sdk::Address,
+    pub amount: i128,
+    pub token: soroban_sdk::Address,
+    pub category: PayoutReason,
+    pub receiver_memo: i128,
+}
+
+/// One `PayoutMade` per token transfer in an executed payout, plus a
+/// `RevenueSharePaid` for each revenue share.
+pub fn publish_payouts(e: &Env, escrow: &Escrow, plan: &Vec<PayoutTransfer>) {
+    for transfer in plan.iter() {
+        if transfer.reason == PayoutReason::RevenueShare {
+            RevenueSharePaid {
+                engagement_id: escrow.engagement_id.clone(),
+                recipient: transfer.recipient.clone(),
+                amount: transfer.amount,
+            }
+            .publish(e);
+        }
+        PayoutMade {
+            engagement_id: escrow.engagement_id.clone(),
+            recipient: transfer.recipient,
+            amount: transfer.amount,
+            token: escrow.trustline.address.clone(),
+            category: transfer.reason,
+            receiver_memo: escrow.receiver_memo,
+        }
+        .publish(e);
+    }
 }
 
 // Admin / TTL
-#[contractevent(topics = ["tw_ttl_extend"], data_format = "vec")]
+/// Protocol configuration is not tied to an escrow, so there is no engagement id.
+#[contractevent(topics = ["tw_fee_update", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct TrustlessWorkFeeUpdated {
+    pub previous: u32,
+    pub current: u32,
+}
+
+#[contractevent(topics = ["tw_addr_update", "v1"], data_format = "vec")]
+#[derive(Clone)]
+pub struct TrustlessWorkAddressUpdated {
+    pub previous: soroban_sdk::Address,
+    pub current: soroban_sdk::Address,
+}
+
+#[contractevent(topics = ["tw_ttl_extend", "v1"], data_format = "vec")]
 #[derive(Clone)]
 pub struct ExtTtlEvt {
+    #[topic]
+    pub engagement_id: String,
     pub platform: soroban_sdk::Address,
     pub ledgers_to_extend: u32,
 }
@@ -2,16 +2,24 @@
 
 mod contract;
 mod core {
+    pub mod admin;
     pub mod dispute;
     pub mod escrow;
+    pub mod evidence;
+    pub mod funding;
     pub mod milestone;
+    pub mod payout;
+    pub use admin::*;
     pub use dispute::*;
     pub use escrow::*;
+    pub use evidence::*;
+    pub use funding::*;
     pub use milestone::*;
     pub mod validators {
         pub mod dispute;
         pub mod escrow;
         pub mod milestone;
+        pub mod status;
     }
 }
 mod error;
@@ -21,10 +29,12 @@ mod events {
 mod modules {
     pub mod math {
         pub mod basic;
-        pub mod safe;
+        pub mod bps;
+        pub mod wide;
 
         pub use basic::*;
-        pub use safe::*;
+        pub use bps::*;
+        pub use wide::*;
     }
 
     pub mod fee {
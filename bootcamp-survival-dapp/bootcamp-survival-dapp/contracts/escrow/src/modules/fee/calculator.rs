@@ -1,13 +1,20 @@
 use crate::{
     error::ContractError,
-    modules::{
-        math::{BasicArithmetic, BasicMath},
-        math::{SafeArithmetic, SafeMath},
+    modules::math::{
+        BasicArithmetic, BasicMath, Bps, Rounding, WideArithmetic, WideMath, BPS_DENOMINATOR,
     },
+    storage::types::{FeeSchedule, FeeTotals},
 };
 
-const TRUSTLESS_WORK_FEE_BPS: u32 = 30;
-const BASIS_POINTS_DENOMINATOR: i128 = 10000;
+/// Rate stored at deployment until the admin changes it.
+pub const DEFAULT_TRUSTLESS_WORK_FEE_BPS: u32 = 30;
+pub const MAX_TRUSTLESS_WORK_FEE_BPS: u32 = 1_000;
+pub const BASIS_POINTS_DENOMINATOR: i128 = BPS_DENOMINATOR as i128;
+
+/// The protocol fee rounds in the protocol's favour; the platform fee rounds
+/// in the receiver's favour.
+pub const TRUSTLESS_WORK_FEE_ROUNDING: Rounding = Rounding::Ceil;
+pub const PLATFORM_FEE_ROUNDING: Rounding = Rounding::Floor;
 
 #[derive(Debug, Clone)]
 pub struct StandardFeeResult {
@@ -19,8 +26,18 @@ pub struct StandardFeeResult {
 pub trait FeeCalculatorTrait {
     fn calculate_standard_fees(
         total_amount: i128,
+        trustless_work_fee_bps: u32,
         platform_fee_bps: u32,
+        platform_fee_schedule: &FeeSchedule,
+        totals: &FeeTotals,
     ) -> Result<StandardFeeResult, ContractError>;
+
+    fn calculate_platform_fee(
+        amount: i128,
+        platform_fee_bps: u32,
+        schedule: &FeeSchedule,
+        totals: &FeeTotals,
+    ) -> Result<i128, ContractError>;
 }
 
 #[derive(Clone)]
@@ -29,17 +46,23 @@ pub struct FeeCalculator;
 impl FeeCalculatorTrait for FeeCalculator {
     fn calculate_standard_fees(
         total_amount: i128,
+        trustless_work_fee_bps: u32,
         platform_fee_bps: u32,
+        platform_fee_schedule: &FeeSchedule,
+        totals: &FeeTotals,
     ) -> Result<StandardFeeResult, ContractError> {
-        let trustless_work_fee = SafeMath::safe_mul_div(
-            total_amount,
-            TRUSTLESS_WORK_FEE_BPS,
-            BASIS_POINTS_DENOMINATOR,
-        )?;
-        let platform_fee =
-            SafeMath::safe_mul_div(total_amount, platform_fee_bps, BASIS_POINTS_DENOMINATOR)?;
-
+        let trustless_work_fee =
+            Bps::new(trustless_work_fee_bps)?.apply(total_amount, TRUSTLESS_WORK_FEE_ROUNDING)?;
         let after_tw = BasicMath::safe_sub(total_amount, trustless_work_fee)?;
+        // A floor can never take more than what is left after the protocol fee;
+        // whatever is left unpaid is charged on the next payout.
+        let platform_fee = Self::calculate_platform_fee(
+            total_amount,
+            platform_fee_bps,
+            platform_fee_schedule,
+            totals,
+        )?
+        .min(after_tw);
         let receiver_amount = BasicMath::safe_sub(after_tw, platform_fee)?;
 
         Ok(StandardFeeResult {
@@ -48,4 +71,67 @@ impl FeeCalculatorTrait for FeeCalculator {
             receiver_amount,
         })
     }
+
+    /// The part of the cumulative platform fee that falls on this payout.
+    fn calculate_platform_fee(
+        amount: i128,
+        platform_fee_bps: u32,
+        schedule: &FeeSchedule,
+        totals: &FeeTotals,
+    ) -> Result<i128, ContractError> {
+        if amount <= 0 {
+            return Ok(0);
+        }
+
+        let volume = BasicMath::safe_add(totals.volume, amount)?;
+        let total_fee = Self::cumulative_platform_fee(volume, platform_fee_bps, schedule)?;
+        Ok(BasicMath::safe_sub(total_fee, totals.platform_fee)?.max(0))
+    }
+}
+
+impl FeeCalculator {
+    /// Platform fee owed on `volume` paid out over the life of the escrow.
+    fn cumulative_platform_fee(
+        volume: i128,
+        platform_fee_bps: u32,
+        schedule: &FeeSchedule,
+    ) -> Result<i128, ContractError> {
+        let mut fee = if schedule.tiers.is_empty() {
+            Bps::new(platform_fee_bps)?.apply(volume, PLATFORM_FEE_ROUNDING)?
+        } else {
+            // Whole units per tier plus the leftover fractions, rounded once at the end.
+            let mut whole: i128 = 0;
+            let mut fractions: i128 = 0;
+            for (index, tier) in schedule.tiers.iter().enumerate() {
+                if volume <= tier.from_amount {
+                    break;
+                }
+                let upper = schedule
+                    .tiers
+                    .get(index as u32 + 1)
+                    .map_or(volume, |next| next.from_amount.min(volume));
+                let portion = BasicMath::safe_sub(upper, tier.from_amount)?;
+                let (tier_fee, fraction) = WideMath::mul_div_rem(
+                    portion,
+                    Bps::new(tier.fee_bps)?.value().into(),
+                    BASIS_POINTS_DENOMINATOR,
+                )?;
+                whole = BasicMath::safe_add(whole, tier_fee)?;
+                fractions = BasicMath::safe_add(fractions, fraction)?;
+            }
+            let rounded = WideMath::mul_div(
+                fractions,
+                1,
+                BASIS_POINTS_DENOMINATOR,
+                PLATFORM_FEE_ROUNDING,
+            )?;
+            BasicMath::safe_add(whole, rounded)?
+        };
+
+        fee = fee.max(schedule.min_fee);
+        if let Some(max_fee) = schedule.max_fee {
+            fee = fee.min(max_fee);
+        }
+        Ok(fee)
+    }
 }
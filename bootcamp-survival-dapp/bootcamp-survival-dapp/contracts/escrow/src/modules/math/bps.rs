@@ -0,0 +1,27 @@
+use crate::error::ContractError;
+
+use super::wide::{Rounding, WideArithmetic, WideMath};
+
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// A rate in basis points, at most 100%.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Bps(u32);
+
+impl Bps {
+    pub fn new(value: u32) -> Result<Self, ContractError> {
+        if value > BPS_DENOMINATOR {
+            return Err(ContractError::Overflow);
+        }
+        Ok(Bps(value))
+    }
+
+    pub fn value(self) -> u32 {
+        self.0
+    }
+
+    /// This rate of `amount`, rounded as requested.
+    pub fn apply(self, amount: i128, rounding: Rounding) -> Result<i128, ContractError> {
+        WideMath::mul_div(amount, self.0.into(), BPS_DENOMINATOR.into(), rounding)
+    }
+}
@@ -0,0 +1,110 @@
+use crate::error::ContractError;
+
+/// Direction a division result is rounded in when it is not exact.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Towards negative infinity.
+    Floor,
+    /// Towards positive infinity.
+    Ceil,
+    /// To the nearest value, ties to the even one.
+    HalfEven,
+}
+
+pub struct WideMath;
+
+/// `a * b / d` with a 256-bit intermediate product, so it only fails when the
+/// final result does not fit in an `i128`.
+pub trait WideArithmetic {
+    fn mul_div(a: i128, b: i128, d: i128, rounding: Rounding) -> Result<i128, ContractError>;
+    /// Floored quotient and remainder for non-negative operands.
+    fn mul_div_rem(a: i128, b: i128, d: i128) -> Result<(i128, i128), ContractError>;
+}
+
+impl WideArithmetic for WideMath {
+    fn mul_div(a: i128, b: i128, d: i128, rounding: Rounding) -> Result<i128, ContractError> {
+        if d == 0 {
+            return Err(ContractError::DivisionError);
+        }
+        let negative = (a < 0) ^ (b < 0) ^ (d < 0) && a != 0 && b != 0;
+        let divisor = d.unsigned_abs();
+        let (hi, lo) = mul_u128(a.unsigned_abs(), b.unsigned_abs());
+        let (mut quotient, remainder) = div_u256(hi, lo, divisor)?;
+
+        if remainder != 0 {
+            let round_away = match rounding {
+                Rounding::Floor => negative,
+                Rounding::Ceil => !negative,
+                // remainder < divisor <= 2^127, so doubling cannot overflow
+                Rounding::HalfEven => match (remainder << 1).cmp(&divisor) {
+                    core::cmp::Ordering::Greater => true,
+                    core::cmp::Ordering::Less => false,
+                    core::cmp::Ordering::Equal => quotient & 1 == 1,
+                },
+            };
+            if round_away {
+                quotient = quotient.checked_add(1).ok_or(ContractError::Overflow)?;
+            }
+        }
+
+        if negative {
+            0i128
+                .checked_sub_unsigned(quotient)
+                .ok_or(ContractError::Underflow)
+        } else {
+            i128::try_from(quotient).map_err(|_| ContractError::Overflow)
+        }
+    }
+
+    fn mul_div_rem(a: i128, b: i128, d: i128) -> Result<(i128, i128), ContractError> {
+        if a < 0 || b < 0 || d <= 0 {
+            return Err(ContractError::DivisionError);
+        }
+        let (hi, lo) = mul_u128(a as u128, b as u128);
+        let (quotient, remainder) = div_u256(hi, lo, d as u128)?;
+        let quotient = i128::try_from(quotient).map_err(|_| ContractError::Overflow)?;
+        // remainder < d, which is an i128
+        Ok((quotient, remainder as i128))
+    }
+}
+
+/// Full 256-bit product of two `u128`s as `(high, low)` halves.
+fn mul_u128(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let cross = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let lo = (cross << 64) | (lo_lo & MASK);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64);
+    (hi, lo)
+}
+
+/// Divides the 256-bit value `(hi, lo)` by `d`. Fails when the quotient
+/// does not fit in 128 bits.
+fn div_u256(hi: u128, lo: u128, d: u128) -> Result<(u128, u128), ContractError> {
+    if hi >= d {
+        return Err(ContractError::Overflow);
+    }
+    if hi == 0 {
+        return Ok((lo / d, lo % d));
+    }
+
+    let mut remainder = hi;
+    let mut quotient: u128 = 0;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= d {
+            remainder = remainder.wrapping_sub(d);
+            quotient |= 1;
+        }
+    }
+    Ok((quotient, remainder))
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, String, Vec};
+use soroban_sdk::{contracttype, Address, BytesN, Map, String, Vec};
 
 #[contracttype]
 #[derive(Clone, PartialEq, Eq)]
@@ -9,19 +9,59 @@ pub struct Escrow {
     pub description: String,
     pub amount: i128,
     pub platform_fee: u32,
+    pub trustless_work_fee: u32,
+    pub platform_fee_schedule: FeeSchedule,
+    pub revenue_shares: Vec<RevenueShare>,
     pub milestones: Vec<Milestone>,
-    pub flags: Flags,
+    pub status: EscrowStatus,
     pub trustline: Trustline,
     pub receiver_memo: i128,
+    pub max_revisions: Option<u32>,
+    pub dispute_resolution_window: Option<u64>,
+    pub dispute_fallback: DisputeFallback,
+    pub arbitration_panel: Vec<Address>,
+    pub panel_threshold: u32,
+    pub appeal_window: Option<u64>,
+    pub appeal_resolver: Option<Address>,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Milestone {
     pub description: String,
-    pub status: String,
+    pub status: MilestoneStatus,
     pub evidence: String,
-    pub approved: bool,
+    pub amount: i128,
+    pub released: bool,
+    pub due_ledger_timestamp: Option<u64>,
+    pub expired: bool,
+    pub revisions: u32,
+    pub revision_reason: Option<String>,
+    pub disputed: bool,
+}
+
+/// Counts kept next to the per-index milestone entries so whole-escrow
+/// checks do not have to load every milestone.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MilestoneHeader {
+    pub count: u32,
+    /// Milestones that were released or expired.
+    pub settled: u32,
+    /// Set by `release_funds`, which pays out every unsettled milestone at
+    /// once. Those entries are not rewritten; reads report them as released.
+    pub released_all: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MilestoneStatus {
+    Pending,
+    InProgress,
+    Submitted,
+    ChangesRequested,
+    Approved,
+    Rejected,
 }
 
 #[contracttype]
@@ -36,11 +76,64 @@ pub struct Roles {
 }
 
 #[contracttype]
-#[derive(Clone, PartialEq, Eq)]
-pub struct Flags {
-    pub disputed: bool,
-    pub released: bool,
-    pub resolved: bool,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EscrowStatus {
+    Draft,
+    Funded,
+    InProgress,
+    Disputed,
+    Released,
+    Resolved,
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisputeFallback {
+    RefundFunders,
+    PayReceiver,
+    SplitByApprovedMilestones,
+}
+
+/// Marginal platform fee bracket: `fee_bps` applies to the part of a payout
+/// from `from_amount` up to the next tier.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeTier {
+    pub from_amount: i128,
+    pub fee_bps: u32,
+}
+
+/// Platform fee over the whole life of the escrow. Tiers (or, without tiers,
+/// the flat `platform_fee` rate) apply to the cumulative fee-bearing volume
+/// and the total is clamped to `min_fee`/`max_fee`; each payout is charged
+/// whatever that total has grown by since the previous ones.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeSchedule {
+    pub tiers: Vec<FeeTier>,
+    pub min_fee: i128,
+    pub max_fee: Option<i128>,
+}
+
+/// Running totals behind the cumulative fee schedule, kept next to the
+/// escrow header.
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeeTotals {
+    /// Payouts that carried the standard fees, before fees.
+    pub volume: i128,
+    /// Platform fee charged so far, revenue shares included.
+    pub platform_fee: i128,
+}
+
+/// Cut of the platform fee paid to a partner or referrer, in basis points of
+/// that fee. `platform_address` keeps whatever the shares leave.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevenueShare {
+    pub recipient: Address,
+    pub bps: u32,
 }
 
 #[contracttype]
@@ -57,9 +150,107 @@ pub struct AddressBalance {
     pub trustline_decimals: u32,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contribution {
+    pub funder: Address,
+    pub amount: i128,
+    pub first_funded_at: u64,
+    pub last_funded_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvidenceSubmission {
+    pub hash: BytesN<32>,
+    pub uri: String,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvidenceEntry {
+    pub hash: BytesN<32>,
+    pub uri: String,
+    pub submitter: Address,
+    pub submitted_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisputeInfo {
+    pub opened_by: Address,
+    pub reason: Option<String>,
+    pub opened_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ruling {
+    pub distributions: Map<Address, i128>,
+    pub ruled_at: u64,
+    /// When the ruling was appealed, if it was.
+    pub appealed_at: Option<u64>,
+    /// Basis-point shares for rulings given by `resolve_dispute_by_shares`,
+    /// empty otherwise. `distributions` then only records the split of the
+    /// balance at ruling time; execution splits the balance held then.
+    pub shares: Map<Address, u32>,
+    /// Receives the rounding dust of `shares`; set with them.
+    pub dust_recipient: Option<Address>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisputeStatement {
+    pub author: Address,
+    pub text_hash: BytesN<32>,
+    pub uri: String,
+    pub submitted_at: u64,
+}
+
+/// Protocol settings kept under `DataKey::Admin`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolConfig {
+    pub admin: Address,
+    pub trustless_work_address: Address,
+    pub trustless_work_fee: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayoutReason {
+    TrustlessWorkFee,
+    PlatformFee,
+    RevenueShare,
+    Release,
+    Distribution,
+    Refund,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PayoutTransfer {
+    pub recipient: Address,
+    pub amount: i128,
+    pub reason: PayoutReason,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
     Escrow,
     Admin,
+    Funders,
+    Contribution(Address),
+    EvidenceCount(u32),
+    Evidence(u32, u32),
+    Dispute,
+    DisputeProposals,
+    MilestoneDisputeProposals(u32),
+    Ruling,
+    DisputeStatementCount,
+    DisputeStatement(u32),
+    MilestoneHeader,
+    Milestone(u32),
+    FeeTotals,
 }